@@ -0,0 +1,72 @@
+/*
+
+Throughput of the table-driven hex and base64 codecs against the generic
+radix engine they replaced, on the challenge inputs in `files/`.
+
+    cargo bench --bench conversion
+
+*/
+#![allow(dead_code, unused_imports, special_module_name)]
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+
+#[path = "../src/lib/mod.rs"]
+mod lib;
+
+use lib::conversion::base64::{self, ClassicBase64};
+use lib::conversion::hex::{self, ClassicHex};
+use lib::conversion::radix::{BASE64, HEX};
+
+fn base64_throughput(c: &mut Criterion) {
+    let text = include_str!("../files/pset1challenge6.txt");
+    let encoded: String = text.split_whitespace().collect();
+    let decoded = base64::decode(&encoded).unwrap();
+
+    let mut group = c.benchmark_group("base64");
+    group.throughput(Throughput::Bytes(decoded.len() as u64));
+    group.bench_function("encode/table", |b| {
+        b.iter(|| base64::encode(black_box(&decoded)))
+    });
+    group.bench_function("encode/radix", |b| {
+        b.iter(|| BASE64.encode(&ClassicBase64 {}, black_box(&decoded), true))
+    });
+    group.bench_function("decode/table", |b| {
+        b.iter(|| base64::decode(black_box(&encoded)).unwrap())
+    });
+    group.bench_function("decode/radix", |b| {
+        b.iter(|| {
+            BASE64
+                .decode(&ClassicBase64 {}, black_box(&encoded), true)
+                .unwrap()
+        })
+    });
+    group.finish();
+}
+
+fn hex_throughput(c: &mut Criterion) {
+    let text = include_str!("../files/pset1challenge4.txt");
+    let encoded: String = text.split_whitespace().collect();
+    let decoded = hex::decode(&encoded).unwrap();
+
+    let mut group = c.benchmark_group("hex");
+    group.throughput(Throughput::Bytes(decoded.len() as u64));
+    group.bench_function("encode/table", |b| {
+        b.iter(|| hex::encode(black_box(&decoded)))
+    });
+    group.bench_function("encode/radix", |b| {
+        b.iter(|| HEX.encode(&ClassicHex {}, black_box(&decoded), false))
+    });
+    group.bench_function("decode/table", |b| {
+        b.iter(|| hex::decode(black_box(&encoded)).unwrap())
+    });
+    group.bench_function("decode/radix", |b| {
+        b.iter(|| {
+            HEX.decode(&ClassicHex {}, black_box(&encoded), false)
+                .unwrap()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, base64_throughput, hex_throughput);
+criterion_main!(benches);
@@ -0,0 +1,164 @@
+/*
+
+Score raw bytes instead of whatever `String::from_utf8_lossy` leaves of them.
+Every byte (or whole UTF-8 character) gets a probability under a simple
+model of text in one language: letters, accented ones included, follow that
+language's frequencies, punctuation and digits are uncommon, while control
+bytes, other non-ASCII and invalid UTF-8 are close to impossible. Random
+bytes land in those last classes all the time, so they sink even when they
+happen to hold plenty of `e`s and spaces.
+
+*/
+use super::{Lang, Scorer, OTHER_FREQ};
+
+/// Of the letters, how many are capitals
+const UPPERCASE_SHARE: f64 = 0.05;
+
+const COMMON_PUNCTUATION: &[u8] = b".,'\"-!?;:()";
+const COMMON_PUNCTUATION_SHARE: f64 = 0.035;
+const DIGIT_SHARE: f64 = 0.01;
+const NEWLINE: f64 = 0.01;
+const TAB_OR_RETURN: f64 = 0.002;
+/// `#`, `@`, `{` and the rest of printable ASCII
+const RARE_PUNCTUATION: f64 = 0.0001;
+/// A symbol past ASCII that isn't a letter
+const NON_ASCII: f64 = 1e-4;
+const CONTROL: f64 = 1e-7;
+const INVALID_UTF8: f64 = 1e-9;
+
+/// Average log probability per character, so lengths compare. Higher is better.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ByteLikelihood {
+    /// Whose letter frequencies and accents count as text
+    pub language: Lang,
+}
+
+impl ByteLikelihood {
+    /// Log probability of all of `bytes`, summed rather than averaged
+    pub(super) fn log_likelihood(&self, bytes: &[u8]) -> f64 {
+        self.events(bytes).0
+    }
+
+    fn events(&self, bytes: &[u8]) -> (f64, usize) {
+        let profile = self.language.profile();
+        let frequencies = profile.frequencies();
+        let mut total = 0.0;
+        let mut count = 0;
+
+        for chunk in bytes.utf8_chunks() {
+            for c in chunk.valid().chars() {
+                total += match c {
+                    _ if c.is_ascii() => ascii_probability(&frequencies, c as u8),
+                    _ if c.is_alphabetic() => profile.accent_frequency(c) * (1.0 - OTHER_FREQ),
+                    _ => NON_ASCII,
+                }
+                .ln();
+                count += 1;
+            }
+            // every stray byte is its own, very unlikely, event
+            total += INVALID_UTF8.ln() * chunk.invalid().len() as f64;
+            count += chunk.invalid().len();
+        }
+
+        (total, count)
+    }
+}
+
+impl Scorer for ByteLikelihood {
+    fn score(&self, candidate: &[u8]) -> f64 {
+        match self.events(candidate) {
+            (_, 0) => INVALID_UTF8.ln(),
+            (total, count) => total / count as f64,
+        }
+    }
+}
+
+fn ascii_probability(frequencies: &[f64; 27], byte: u8) -> f64 {
+    let text_share = 1.0 - OTHER_FREQ;
+    match byte {
+        b'a'..=b'z' => frequencies[(byte - b'a') as usize] * text_share * (1.0 - UPPERCASE_SHARE),
+        b'A'..=b'Z' => frequencies[(byte - b'A') as usize] * text_share * UPPERCASE_SHARE,
+        b' ' => frequencies[26] * text_share,
+        b'0'..=b'9' => DIGIT_SHARE / 10.0,
+        b'\n' => NEWLINE,
+        b'\t' | b'\r' => TAB_OR_RETURN,
+        _ if COMMON_PUNCTUATION.contains(&byte) => {
+            COMMON_PUNCTUATION_SHARE / COMMON_PUNCTUATION.len() as f64
+        }
+        _ if byte.is_ascii_punctuation() => RARE_PUNCTUATION,
+        _ => CONTROL,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lib::analysis::LetterFrequency;
+    use crate::lib::manipulate::{guess_xor_message_one_char_using_scorer, xor_bytes_with_char};
+
+    const REAL: &[u8] = b"I go crazy when I hear a cymbal";
+
+    /// Candidates built to fool a letter count: lots of `e`s and spaces, wrapped in junk
+    fn adversaries() -> Vec<Vec<u8>> {
+        vec![
+            b"e \x00".repeat(20),
+            b"e \xff".repeat(20),
+            b"e\xe9 ".repeat(20),
+            b"\x1b[0m e e e e e e e e e e e e e e e e \x07\x07".to_vec(),
+            "e \u{FFFD}".repeat(20).into_bytes(),
+        ]
+    }
+
+    #[test]
+    fn letter_counts_fall_for_it() {
+        for adversary in adversaries() {
+            assert!(
+                LetterFrequency::default().score(&adversary)
+                    > LetterFrequency::default().score(REAL)
+            );
+        }
+    }
+
+    #[test]
+    fn should_not_fall_for_it() {
+        for adversary in adversaries() {
+            assert!(
+                ByteLikelihood::default().score(REAL) > ByteLikelihood::default().score(&adversary),
+                "{:?}",
+                String::from_utf8_lossy(&adversary)
+            );
+        }
+    }
+
+    #[test]
+    fn should_rank_byte_classes() {
+        let score = |bytes: &[u8]| ByteLikelihood::default().score(bytes);
+        assert!(score(b"etaoin") > score(b"ETAOIN"));
+        assert!(score(b"ETAOIN") > score(b"#@{}^~"));
+        assert!(score("né".as_bytes()) > score(b"n\xe9"));
+        assert!(score(b"#@{}^~") > score(b"\x01\x02\x03\x04\x05\x06"));
+        assert!(score(b"\x01") > score(b"\xff"));
+        assert_eq!(score(b""), score(b"\xff"));
+    }
+
+    #[test]
+    fn should_break_xor_without_lossy_text() {
+        let original = b"Now that the party is jumping\n";
+        let xored = xor_bytes_with_char(original, 0x35);
+        let answer =
+            guess_xor_message_one_char_using_scorer(&ByteLikelihood::default(), &xored).unwrap();
+        assert_eq!(answer.answer, '5');
+    }
+
+    #[test]
+    fn should_break_xor_on_other_languages() {
+        let original =
+            "Über den Wolken muss die Freiheit wohl grenzenlos sein, sagt man, und es stimmt.";
+        let xored = xor_bytes_with_char(original.as_bytes(), 0xA3);
+        let german = ByteLikelihood {
+            language: Lang::German,
+        };
+        let answer = guess_xor_message_one_char_using_scorer(&german, &xored).unwrap();
+        assert_eq!(answer.decoded, original);
+    }
+}
@@ -0,0 +1,220 @@
+/*
+
+Pearson's chi-squared test of a byte string against a language's letter
+frequencies. There are 28 buckets: the 26 letters (either case), space, and
+everything else, accented letters' bytes included. Counting bytes, not
+chars, keeps every total consistent, and since the "other" bucket always
+expects something, no input divides by zero.
+
+The raw statistic grows with length, so candidates of different lengths are
+compared through `per_byte` or `p_value` instead.
+
+*/
+use super::{Lang, OTHER_FREQ};
+
+const BUCKETS: usize = 28;
+const OTHER: usize = 27;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChiSquaredTest {
+    /// Pearson's statistic. The SMALLER the number, the more it resembles english.
+    pub statistic: f64,
+    pub degrees_of_freedom: usize,
+    /// Chance that real english this long would look at least this far off
+    pub p_value: f64,
+    /// The statistic over the input length, the effect size, which doesn't grow with length
+    pub per_byte: f64,
+}
+
+/// Test `bytes` against english. Empty input shows no difference from english at all.
+pub fn chi2_english_test(bytes: &[u8]) -> ChiSquaredTest {
+    chi2_test(bytes, Lang::English)
+}
+
+/// Test `bytes` against `language`, the same way as `chi2_english_test`
+pub fn chi2_test(bytes: &[u8], language: Lang) -> ChiSquaredTest {
+    let degrees_of_freedom = BUCKETS - 1;
+    if bytes.is_empty() {
+        return ChiSquaredTest {
+            statistic: 0.0,
+            degrees_of_freedom,
+            p_value: 1.0,
+            per_byte: 0.0,
+        };
+    }
+
+    let mut counts = [0_usize; BUCKETS];
+    for &byte in bytes {
+        let bucket = match byte {
+            b'a'..=b'z' => (byte - b'a') as usize,
+            b'A'..=b'Z' => (byte - b'A') as usize,
+            b' ' => 26,
+            _ => OTHER,
+        };
+        counts[bucket] += 1;
+    }
+
+    let frequencies = language.profile().frequencies();
+    let accented = 1.0 - frequencies.iter().sum::<f64>();
+    let length = bytes.len() as f64;
+    let statistic: f64 = counts
+        .iter()
+        .enumerate()
+        .map(|(bucket, &observed)| {
+            let frequency = match bucket {
+                OTHER => OTHER_FREQ + accented * (1.0 - OTHER_FREQ),
+                _ => frequencies[bucket] * (1.0 - OTHER_FREQ),
+            };
+            let expected = length * frequency;
+            let difference = observed as f64 - expected;
+            difference * difference / expected
+        })
+        .sum();
+
+    ChiSquaredTest {
+        statistic,
+        degrees_of_freedom,
+        p_value: upper_regularized_gamma(degrees_of_freedom as f64 / 2.0, statistic / 2.0),
+        per_byte: statistic / length,
+    }
+}
+
+/// Iterations before the series or continued fraction gives up converging
+const MAX_ITERATIONS: usize = 500;
+const EPSILON: f64 = 1e-15;
+const TINY: f64 = 1e-300;
+
+/// Q(a, x), the chi-squared survival function being Q(k/2, x/2).
+/// Follows Numerical Recipes' `gammq`: a series below `a + 1`, a continued fraction above.
+fn upper_regularized_gamma(a: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 1.0;
+    }
+    let prefactor = (-x + a * x.ln() - ln_gamma(a)).exp();
+
+    if x < a + 1.0 {
+        let mut term = 1.0 / a;
+        let mut sum = term;
+        let mut denominator = a;
+        for _ in 0..MAX_ITERATIONS {
+            denominator += 1.0;
+            term *= x / denominator;
+            sum += term;
+            if term.abs() < sum.abs() * EPSILON {
+                break;
+            }
+        }
+        (1.0 - sum * prefactor).clamp(0.0, 1.0)
+    } else {
+        // modified Lentz's method
+        let mut b = x + 1.0 - a;
+        let mut c = 1.0 / TINY;
+        let mut d = 1.0 / b;
+        let mut h = d;
+        for i in 1..=MAX_ITERATIONS {
+            let an = -(i as f64) * (i as f64 - a);
+            b += 2.0;
+            d = an * d + b;
+            if d.abs() < TINY {
+                d = TINY;
+            }
+            c = b + an / c;
+            if c.abs() < TINY {
+                c = TINY;
+            }
+            d = 1.0 / d;
+            let delta = d * c;
+            h *= delta;
+            if (delta - 1.0).abs() < EPSILON {
+                break;
+            }
+        }
+        (prefactor * h).clamp(0.0, 1.0)
+    }
+}
+
+/// ln Γ(x) for x > 0, by Lanczos' approximation
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 6] = [
+        76.18009172947146,
+        -86.50532032941677,
+        24.01409824083091,
+        -1.231739572450155,
+        0.1208650973866179e-2,
+        -0.5395239384953e-5,
+    ];
+    let mut tmp = x + 5.5;
+    tmp -= (x + 0.5) * tmp.ln();
+    let mut series = 1.000000000190015;
+    let mut y = x;
+    for coefficient in COEFFICIENTS {
+        y += 1.0;
+        series += coefficient / y;
+    }
+    -tmp + (2.5066282746310005 * series / x).ln()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-6
+    }
+
+    #[test]
+    fn should_match_known_distribution_values() {
+        assert!(close(ln_gamma(5.0), 24.0_f64.ln()));
+        assert!(close(ln_gamma(0.5), std::f64::consts::PI.sqrt().ln()));
+        // two degrees of freedom has a closed form, e^(-x/2)
+        for x in [0.5, 2.0, 7.0, 30.0] {
+            assert!(close(
+                upper_regularized_gamma(1.0, x / 2.0),
+                (-x / 2.0).exp()
+            ));
+        }
+        // the 5% critical value for 27 degrees of freedom
+        assert!((upper_regularized_gamma(13.5, 40.113 / 2.0) - 0.05).abs() < 1e-4);
+    }
+
+    #[test]
+    fn should_handle_degenerate_input() {
+        let empty = chi2_english_test(b"");
+        assert_eq!(
+            (empty.statistic, empty.p_value, empty.per_byte),
+            (0.0, 1.0, 0.0)
+        );
+
+        for input in ["1234!".as_bytes(), b"\x00\xff\xfe", "éééé".as_bytes()] {
+            let test = chi2_english_test(input);
+            assert!(test.statistic.is_finite() && test.statistic > 0.0);
+            assert!((0.0..=1.0).contains(&test.p_value));
+        }
+        // four chars, eight bytes, all of them "other"
+        assert_eq!(
+            chi2_english_test("éééé".as_bytes()),
+            chi2_english_test(&[0xFF; 8])
+        );
+    }
+
+    #[test]
+    fn should_compare_across_lengths() {
+        let short = chi2_english_test(b"Cooking MC's like a pound of bacon");
+        let long = chi2_english_test(
+            b"Cooking MC's like a pound of bacon, and the rest of the verse follows it down the page",
+        );
+        let garbage = chi2_english_test(b"Ieeacdm*GI-y*fcao*k*zeku");
+
+        assert!(short.per_byte < garbage.per_byte);
+        assert!(long.per_byte < garbage.per_byte);
+        assert!(short.p_value > garbage.p_value);
+        assert!(garbage.p_value < 0.05);
+    }
+
+    #[test]
+    fn should_test_against_other_languages() {
+        let text = b"el perro come la comida y el gato duerme en la casa de la abuela";
+        assert!(chi2_test(text, Lang::Spanish).per_byte < chi2_test(text, Lang::German).per_byte);
+        assert_eq!(chi2_english_test(text), chi2_test(text, Lang::English));
+    }
+}
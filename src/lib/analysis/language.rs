@@ -0,0 +1,315 @@
+/*
+
+Letter frequency profiles for the languages we see traffic in, plus a
+catch-all for Latin-script text in none of them. Letters are stored as a
+share of all letters, accented ones separately, and spaces as a share of
+letters and spaces together, since word length varies by language.
+
+Tables via https://en.wikipedia.org/wiki/Letter_frequency
+
+*/
+use std::fmt;
+
+use super::byte_class::ByteLikelihood;
+use super::ENGLISH_FREQ;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Lang {
+    #[default]
+    English,
+    German,
+    French,
+    Spanish,
+    /// Some Latin-script language, accents and all, without committing to one
+    Latin,
+}
+
+impl Lang {
+    pub const ALL: [Lang; 5] = [
+        Lang::English,
+        Lang::German,
+        Lang::French,
+        Lang::Spanish,
+        Lang::Latin,
+    ];
+
+    pub fn profile(self) -> &'static Profile {
+        match self {
+            Lang::English => &ENGLISH,
+            Lang::German => &GERMAN,
+            Lang::French => &FRENCH,
+            Lang::Spanish => &SPANISH,
+            Lang::Latin => &LATIN,
+        }
+    }
+
+    /// How readily we believe in a language before seeing any text. The
+    /// catch-all fits everything a little, so it only wins when nothing else fits.
+    fn prior(self) -> f64 {
+        match self {
+            Lang::Latin => 0.1,
+            _ => 1.0,
+        }
+    }
+}
+
+impl fmt::Display for Lang {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Lang::English => "English",
+            Lang::German => "German",
+            Lang::French => "French",
+            Lang::Spanish => "Spanish",
+            Lang::Latin => "Latin script",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Profile {
+    /// `a` to `z`, without accents, as a share of letters
+    letters: [f64; 26],
+    /// Lowercase accented letters as a share of letters
+    accents: &'static [(char, f64)],
+    /// Share of letters for each accented letter `accents` doesn't list
+    other_accents: f64,
+    /// Share of letters and spaces
+    space: f64,
+}
+
+impl Profile {
+    /// `a` to `z` then space, as shares of letters and spaces. What's missing
+    /// from a total of one is the accented letters.
+    pub fn frequencies(&self) -> [f64; 27] {
+        let scale = (1.0 - self.space) / self.letter_total();
+        let mut frequencies = [0.0; 27];
+        for (frequency, letter) in frequencies.iter_mut().zip(self.letters) {
+            *frequency = letter * scale;
+        }
+        frequencies[26] = self.space;
+        frequencies
+    }
+
+    /// Share of letters and spaces for an accented letter, either case
+    pub fn accent_frequency(&self, c: char) -> f64 {
+        let lowercase = c.to_lowercase().next().unwrap_or(c);
+        let share = self
+            .accents
+            .iter()
+            .find(|(accent, _)| *accent == lowercase)
+            .map_or(self.other_accents, |&(_, share)| share);
+        share * (1.0 - self.space) / self.letter_total()
+    }
+
+    // the published tables don't quite add up, this puts them back on one
+    fn letter_total(&self) -> f64 {
+        self.letters.iter().sum::<f64>() + self.accents.iter().map(|(_, share)| share).sum::<f64>()
+    }
+}
+
+/// How likely `bytes` is to be in each language, as probabilities adding up to one, best first
+pub fn identify_language(bytes: &[u8]) -> Vec<(Lang, f64)> {
+    let log_likelihoods: Vec<(Lang, f64)> = Lang::ALL
+        .iter()
+        .map(|&language| {
+            let likelihood = ByteLikelihood { language }.log_likelihood(bytes);
+            (language, likelihood + language.prior().ln())
+        })
+        .collect();
+
+    // softmax, shifted by the best so exp() doesn't underflow to nothing
+    let best = log_likelihoods
+        .iter()
+        .map(|&(_, value)| value)
+        .fold(f64::NEG_INFINITY, f64::max);
+    let total: f64 = log_likelihoods
+        .iter()
+        .map(|&(_, value)| (value - best).exp())
+        .sum();
+
+    let mut ranked: Vec<(Lang, f64)> = log_likelihoods
+        .into_iter()
+        .map(|(language, value)| (language, (value - best).exp() / total))
+        .collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+    ranked
+}
+
+const fn first_26(frequencies: [f64; 27]) -> [f64; 26] {
+    let mut letters = [0.0; 26];
+    let mut i = 0;
+    while i < 26 {
+        letters[i] = frequencies[i];
+        i += 1;
+    }
+    letters
+}
+
+const fn average(tables: [[f64; 26]; 4]) -> [f64; 26] {
+    let mut letters = [0.0; 26];
+    let mut i = 0;
+    while i < 26 {
+        letters[i] = (tables[0][i] + tables[1][i] + tables[2][i] + tables[3][i]) / 4.0;
+        i += 1;
+    }
+    letters
+}
+
+/// Accented letters that turn up in English now and then: café, naïve
+const RARE_ACCENT: f64 = 0.0001;
+
+const ENGLISH_LETTERS: [f64; 26] = first_26(ENGLISH_FREQ);
+
+const GERMAN_LETTERS: [f64; 26] = [
+    0.06516, 0.01886, 0.02732, 0.05076, 0.16396, 0.01656, 0.03009, // A-G
+    0.04577, 0.06550, 0.00268, 0.01417, 0.03437, 0.02534, 0.09776, // H-N
+    0.02594, 0.00670, 0.00018, 0.07003, 0.07270, 0.06154, 0.04166, // O-U
+    0.00846, 0.01921, 0.00034, 0.00039, 0.01134, // V-Z
+];
+
+const FRENCH_LETTERS: [f64; 26] = [
+    0.07636, 0.00901, 0.03260, 0.03669, 0.14715, 0.01066, 0.00866, // A-G
+    0.00737, 0.07529, 0.00613, 0.00074, 0.05456, 0.02968, 0.07095, // H-N
+    0.05796, 0.02521, 0.01362, 0.06693, 0.07948, 0.07244, 0.06311, // O-U
+    0.01838, 0.00049, 0.00427, 0.00128, 0.00326, // V-Z
+];
+
+const SPANISH_LETTERS: [f64; 26] = [
+    0.11525, 0.02215, 0.04019, 0.05010, 0.12181, 0.00692, 0.01768, // A-G
+    0.00703, 0.06247, 0.00493, 0.00011, 0.04967, 0.03157, 0.06712, // H-N
+    0.08683, 0.02510, 0.00877, 0.06871, 0.07977, 0.04632, 0.02927, // O-U
+    0.01138, 0.00017, 0.00215, 0.01008, 0.00467, // V-Z
+];
+
+const ENGLISH: Profile = Profile {
+    letters: ENGLISH_LETTERS,
+    accents: &[],
+    other_accents: RARE_ACCENT,
+    space: ENGLISH_FREQ[26],
+};
+
+const GERMAN: Profile = Profile {
+    letters: GERMAN_LETTERS,
+    accents: &[
+        ('ä', 0.00578),
+        ('ö', 0.00443),
+        ('ü', 0.00995),
+        ('ß', 0.00307),
+    ],
+    other_accents: RARE_ACCENT,
+    // long compounds, fewer breaks
+    space: 0.15,
+};
+
+const FRENCH: Profile = Profile {
+    letters: FRENCH_LETTERS,
+    accents: &[
+        ('à', 0.00486),
+        ('â', 0.00051),
+        ('œ', 0.00018),
+        ('ç', 0.00085),
+        ('è', 0.00271),
+        ('é', 0.01504),
+        ('ê', 0.00218),
+        ('ë', 0.00008),
+        ('î', 0.00045),
+        ('ï', 0.00005),
+        ('ô', 0.00023),
+        ('ù', 0.00058),
+        ('û', 0.00060),
+    ],
+    other_accents: RARE_ACCENT,
+    space: 0.17,
+};
+
+const SPANISH: Profile = Profile {
+    letters: SPANISH_LETTERS,
+    accents: &[
+        ('á', 0.00502),
+        ('é', 0.00433),
+        ('í', 0.00725),
+        ('ñ', 0.00311),
+        ('ó', 0.00827),
+        ('ú', 0.00168),
+        ('ü', 0.00012),
+    ],
+    other_accents: RARE_ACCENT,
+    space: 0.17,
+};
+
+const LATIN: Profile = Profile {
+    letters: average([
+        ENGLISH_LETTERS,
+        GERMAN_LETTERS,
+        FRENCH_LETTERS,
+        SPANISH_LETTERS,
+    ]),
+    accents: &[],
+    // no favourites, but accents are nothing unusual
+    other_accents: 0.002,
+    space: 0.17,
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLES: [(Lang, &str); 4] = [
+        (
+            Lang::English,
+            "The quick brown fox jumps over the lazy dog, and the children look out of the window while it rains outside.",
+        ),
+        (
+            Lang::German,
+            "Der schnelle braune Fuchs springt über den faulen Hund, und die Kinder schauen aus dem Fenster, während es draußen regnet.",
+        ),
+        (
+            Lang::French,
+            "Le renard brun rapide saute par-dessus le chien paresseux, et les enfants regardent par la fenêtre pendant qu'il pleut dehors.",
+        ),
+        (
+            Lang::Spanish,
+            "El rápido zorro marrón salta sobre el perro perezoso, y los niños miran por la ventana mientras llueve afuera.",
+        ),
+    ];
+
+    #[test]
+    fn should_identify_languages() {
+        for (language, text) in SAMPLES {
+            let ranked = identify_language(text.as_bytes());
+            assert_eq!(ranked[0].0, language, "{:?}", ranked);
+            assert_eq!(ranked.len(), Lang::ALL.len());
+            assert!((ranked.iter().map(|(_, p)| p).sum::<f64>() - 1.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn should_fall_back_to_latin_script() {
+        let portuguese = "A menina não sabe se o pão está quente, mas a avó já começou a comê-lo.";
+        assert_eq!(identify_language(portuguese.as_bytes())[0].0, Lang::Latin);
+    }
+
+    #[test]
+    fn should_go_by_priors_alone_for_nothing() {
+        let ranked = identify_language(b"");
+        assert_eq!(ranked.last().unwrap().0, Lang::Latin);
+        assert!((ranked[0].1 - 1.0 / 4.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn profiles_should_add_up() {
+        for language in Lang::ALL {
+            let profile = language.profile();
+            let base: f64 = profile.frequencies().iter().sum();
+            let accents: f64 = profile
+                .accents
+                .iter()
+                .map(|&(accent, _)| profile.accent_frequency(accent))
+                .sum();
+            assert!((base + accents - 1.0).abs() < 1e-9, "{}", language);
+        }
+        assert_eq!(GERMAN.accent_frequency('Ü'), GERMAN.accent_frequency('ü'));
+        assert!(LATIN.accent_frequency('å') > GERMAN.accent_frequency('å'));
+    }
+}
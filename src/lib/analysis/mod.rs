@@ -1,8 +1,20 @@
+mod byte_class;
+mod chi_squared;
+mod language;
+mod ngram;
+mod scorer;
+
 use std::fmt::Display;
 
 use bitvec::prelude::*;
 use rayon::prelude::*;
 
+pub use byte_class::ByteLikelihood;
+pub use chi_squared::{chi2_english_test, chi2_test, ChiSquaredTest};
+pub use language::{identify_language, Lang, Profile};
+pub use ngram::NgramModel;
+pub use scorer::{ChiSquared, LetterFrequency, PrintableRatio, Scorer};
+
 const ASCII_UPPERCASE: usize = 65;
 const ASCII_LOWERCASE: usize = 97;
 // via https://www3.nd.edu/~busiforc/handouts/cryptography/letterfrequencies.html
@@ -13,6 +25,8 @@ const ENGLISH_FREQ: [f64; 27] = [
     0.07507, 0.01929, 0.00095, 0.05987, 0.06327, 0.09056, 0.02758, // O-U
     0.00978, 0.02360, 0.00150, 0.01974, 0.00074, 0.19181, // V-Z, space
 ];
+/// Share of english text that's neither a letter nor a space: punctuation, digits, line breaks
+const OTHER_FREQ: f64 = 0.06;
 
 /// The LARGER the number, the more it resembles english (more rudimentary)
 pub fn english_text_score(string: &str) -> f64 {
@@ -32,49 +46,65 @@ pub fn english_text_score(string: &str) -> f64 {
         .sum()
 }
 
-/// The SMALLER the number, the more it resembles english.
-pub fn get_chi2_english(string: &str) -> f64 {
-    // help from: https://crypto.stackexchange.com/a/30259
-    let mut counts: [f64; 27] = [0.0; 27];
-    let mut ignored_chars: usize = 0;
+/// Like `english_text_score`, but weighed by `language`'s letter frequencies
+pub fn text_score(string: &str, language: Lang) -> f64 {
+    let frequencies = language.profile().frequencies();
+
+    string
+        .chars()
+        .map(|c| match c {
+            'a'..='z' => frequencies[c as usize - ASCII_LOWERCASE],
+            'A'..='Z' => frequencies[c as usize - ASCII_UPPERCASE],
+            ' ' => frequencies[26],
+            _ if c.is_alphabetic() => language.profile().accent_frequency(c),
+            _ => 0.0,
+        })
+        .sum()
+}
 
-    string.chars().for_each(|c| match c {
-        'a'..='z' => counts[c as usize - ASCII_LOWERCASE] += 1.0,
-        'A'..='Z' => counts[c as usize - ASCII_UPPERCASE] += 1.0,
-        ' ' => counts[26] += 1.0,
-        c if c as u8 == 9 || c as u8 == 10 || c as u8 == 13 => ignored_chars += 1,
-        c if c.is_ascii_punctuation() || c.is_ascii_digit() => ignored_chars += 1,
-        _ => ignored_chars += 1, // not ascii perhaps?
-    });
+/// Average quadgram log probability. The LARGER (closer to zero), the more it
+/// resembles english; unlike `english_text_score` it doesn't grow with length.
+pub fn english_ngram_score(bytes: &[u8]) -> f64 {
+    NgramModel::english(4).log_probability(bytes)
+}
 
-    let length = string.len() - ignored_chars;
+/// Average log probability per byte of `bytes` being english text. The LARGER
+/// the number, the more it resembles english; control bytes and invalid UTF-8 sink it.
+pub fn english_byte_score(bytes: &[u8]) -> f64 {
+    ByteLikelihood::default().score(bytes)
+}
 
-    (0..27_usize)
-        .into_par_iter()
-        .map(|i: usize| {
-            let observed = counts[i];
-            let expected = length as f64 * ENGLISH_FREQ[i];
-            let difference = observed - expected;
-            (difference * difference) / expected
-        })
-        .sum()
+/// The SMALLER the number, the more it resembles english. Counts bytes, so
+/// non-ASCII is fine, and anything that isn't a letter or space is weighed too.
+/// Use `chi2_english_test` to compare texts of different lengths.
+pub fn get_chi2_english(string: &str) -> f64 {
+    chi2_english_test(string.as_bytes()).statistic
 }
 
 /// Pick best string among the slice passed in.
 pub fn pick_best_english_string<T: AsRef<str> + Display>(strings: &[T]) -> &str {
-    let mut best_score = f64::MIN;
-    let mut message: &str = "";
+    pick_best_string_using_scorer(&LetterFrequency::default(), strings)
+}
+
+/// Pick the string `scorer` likes best. Ties go to the earliest.
+pub fn pick_best_string_using_scorer<'a, S: Scorer + ?Sized, T: AsRef<str>>(
+    scorer: &S,
+    strings: &'a [T],
+) -> &'a str {
+    let mut best_score = f64::NEG_INFINITY;
+    let mut message: Option<&str> = None;
 
     for s in strings {
-        let score = english_text_score(s.as_ref());
+        let score = scorer.score(s.as_ref().as_bytes());
 
-        if score > best_score {
+        // even a hopeless score beats having nothing
+        if message.is_none() || score > best_score {
             best_score = score;
-            message = s.as_ref();
+            message = Some(s.as_ref());
         }
     }
 
-    message
+    message.unwrap_or_default()
 }
 
 pub fn get_hamming_distance(
@@ -118,7 +148,11 @@ mod tests {
             "Ieeacdm*GI-y*fcao*k*zeku",
         ];
         let best = pick_best_english_string(&sentences);
-        assert_eq!(best, String::from("Cooking MC's like a pound of bacon"))
+        assert_eq!(best, String::from("Cooking MC's like a pound of bacon"));
+        assert_eq!(
+            pick_best_string_using_scorer(&ChiSquared::default(), &sentences),
+            best
+        );
     }
 
     #[test]
@@ -131,6 +165,7 @@ mod tests {
 
         // a higher score is more like english
         assert!(good_str_score > bad_str_score);
+        assert!(english_ngram_score(good_str.as_bytes()) > english_ngram_score(bad_str.as_bytes()));
     }
 
     #[test]
@@ -0,0 +1,218 @@
+/*
+
+N-gram language models. Text is folded down to 27 symbols, the letters and
+a space that stands in for any run of whitespace, digits or punctuation;
+a model is the log probability of every run of `order` symbols. Unlike
+letter counts, this knows that "the" is English and "eht" isn't.
+
+Anything that isn't ASCII text at all, control bytes or non-ASCII, can't
+be part of an n-gram we've seen, so it costs as much as the rarest one.
+
+*/
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use super::Scorer;
+
+/// Public domain English: Lincoln, Jefferson, Austen, Dickens, Melville and friends
+const ENGLISH_CORPUS: &str = include_str!("../../../files/corpus/english.txt");
+
+const SYMBOLS: usize = 27;
+const SPACE: u8 = 26;
+/// Longest n-gram we build tables for, 27^4 entries is already half a million
+const MAX_ORDER: usize = 4;
+
+/// Count given to n-grams the training text never had
+const UNSEEN_COUNT: f64 = 0.01;
+
+#[derive(Debug, Clone)]
+pub struct NgramModel {
+    order: usize,
+    log_probabilities: Vec<f32>,
+    floor: f64,
+}
+
+impl NgramModel {
+    /// Count every n-gram in `text`. `order` goes from 1 (letters) to 4 (quadgrams).
+    pub fn train(order: usize, text: &str) -> NgramModel {
+        assert!(
+            (1..=MAX_ORDER).contains(&order),
+            "n-gram order must be 1 to {}",
+            MAX_ORDER
+        );
+
+        let mut counts = vec![0_u32; SYMBOLS.pow(order as u32)];
+        // nothing in a corpus is garbage, just more word breaks
+        let symbols: Vec<u8> = symbols(text.as_bytes())
+            .map(|symbol| symbol.unwrap_or(SPACE))
+            .collect();
+        for window in symbols.windows(order) {
+            counts[index(window)] += 1;
+        }
+
+        let total = counts
+            .iter()
+            .map(|&count| count as f64)
+            .sum::<f64>()
+            .max(1.0);
+        let floor = (UNSEEN_COUNT / total).ln();
+        let log_probabilities = counts
+            .iter()
+            .map(|&count| match count {
+                0 => floor as f32,
+                _ => (count as f64 / total).ln() as f32,
+            })
+            .collect();
+
+        NgramModel {
+            order,
+            log_probabilities,
+            floor,
+        }
+    }
+
+    /// Train on the contents of every file in `paths`, e.g. a directory of books
+    pub fn train_from_files<P: AsRef<Path>>(order: usize, paths: &[P]) -> io::Result<NgramModel> {
+        let mut text = String::new();
+        for path in paths {
+            text.push_str(&String::from_utf8_lossy(&fs::read(path)?));
+            text.push('\n');
+        }
+        Ok(NgramModel::train(order, &text))
+    }
+
+    /// The model trained on the built-in English corpus, built on first use
+    pub fn english(order: usize) -> &'static NgramModel {
+        static MODELS: [OnceLock<NgramModel>; MAX_ORDER] = [
+            OnceLock::new(),
+            OnceLock::new(),
+            OnceLock::new(),
+            OnceLock::new(),
+        ];
+        assert!(
+            (1..=MAX_ORDER).contains(&order),
+            "n-gram order must be 1 to {}",
+            MAX_ORDER
+        );
+        MODELS[order - 1].get_or_init(|| NgramModel::train(order, ENGLISH_CORPUS))
+    }
+
+    pub fn order(&self) -> usize {
+        self.order
+    }
+
+    /// Average log probability per n-gram, so texts of any length compare.
+    /// Text too short to hold a single n-gram gets the score of an unseen one.
+    pub fn log_probability(&self, text: &[u8]) -> f64 {
+        let symbols: Vec<Option<u8>> = symbols(text).collect();
+        if symbols.len() < self.order {
+            return self.floor;
+        }
+
+        let windows = symbols.windows(self.order);
+        let count = windows.len();
+        let total: f64 = windows
+            .map(|window| {
+                let known: Option<Vec<u8>> = window.iter().copied().collect();
+                match known {
+                    Some(window) => self.log_probabilities[index(&window)] as f64,
+                    None => self.floor,
+                }
+            })
+            .sum();
+
+        total / count as f64
+    }
+}
+
+impl Scorer for NgramModel {
+    fn score(&self, candidate: &[u8]) -> f64 {
+        self.log_probability(candidate)
+    }
+}
+
+/// Fold `text` to symbols, `None` for bytes that can't be text. Starts and ends
+/// with a space so the first and last words count as words.
+fn symbols(text: &[u8]) -> impl Iterator<Item = Option<u8>> + '_ {
+    let mut previous = None;
+    std::iter::once(b' ')
+        .chain(text.iter().copied())
+        .chain(std::iter::once(b' '))
+        .filter_map(move |byte| {
+            let symbol = match byte {
+                b'a'..=b'z' => Some(byte - b'a'),
+                b'A'..=b'Z' => Some(byte - b'A'),
+                b'0'..=b'9' | b' ' | b'\t' | b'\n' | b'\r' => Some(SPACE),
+                _ if byte.is_ascii_punctuation() => Some(SPACE),
+                _ => None,
+            };
+            // a run of breaks is a single break
+            let repeat = symbol == Some(SPACE) && previous == Some(Some(SPACE));
+            previous = Some(symbol);
+            (!repeat).then_some(symbol)
+        })
+}
+
+fn index(window: &[u8]) -> usize {
+    window
+        .iter()
+        .fold(0, |index, &symbol| index * SYMBOLS + symbol as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lib::analysis::english_text_score;
+    use crate::lib::manipulate::{guess_xor_message_one_char_using_scorer, xor_bytes_with_char};
+
+    #[test]
+    fn should_know_word_order_not_just_letters() {
+        let real = "the cat sat on the mat";
+        // the very same letters, so letter frequencies can't tell them apart
+        let scrambled = "eht tac tas no eht tam";
+        assert_eq!(english_text_score(real), english_text_score(scrambled));
+        for order in 2..=4 {
+            let model = NgramModel::english(order);
+            assert!(
+                model.score(real.as_bytes()) > model.score(scrambled.as_bytes()),
+                "order {}",
+                order
+            );
+        }
+    }
+
+    #[test]
+    fn should_compare_across_lengths() {
+        let model = NgramModel::english(4);
+        let short = model.score(b"Now that the party is jumping");
+        let long = model.score(
+            b"Now that the party is jumping, with the bass kicked in and the vegas are pumping",
+        );
+        let garbage = model.score(b"qzx vkjw pqqz hhhh");
+        assert!((short - long).abs() < (short - garbage).abs());
+        assert!(model.score(b"the \x00\x01 cat") < model.score(b"the cat"));
+        assert_eq!(model.score(b""), model.score(b"\xff\xfe"));
+    }
+
+    #[test]
+    fn should_break_single_byte_xor() {
+        let original = "Cooking MC's like a pound of bacon";
+        let xored = xor_bytes_with_char(original.as_bytes(), b'X');
+        let answer =
+            guess_xor_message_one_char_using_scorer(NgramModel::english(3), &xored).unwrap();
+        assert_eq!(answer.decoded, original);
+    }
+
+    #[test]
+    fn should_train_from_files() {
+        let model = NgramModel::train_from_files(2, &["files/corpus/english.txt"]).unwrap();
+        assert_eq!(model.order(), 2);
+        assert_eq!(
+            model.score(b"Call me Ishmael"),
+            NgramModel::english(2).score(b"Call me Ishmael")
+        );
+        assert!(NgramModel::train_from_files(2, &["files/corpus/missing.txt"]).is_err());
+    }
+}
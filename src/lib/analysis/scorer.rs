@@ -0,0 +1,108 @@
+use super::{chi2_test, text_score, Lang};
+
+/// Ranks candidate plaintexts, e.g. the 256 outputs of a single-byte XOR.
+/// Whatever the statistic underneath, the LARGER the number, the better.
+pub trait Scorer: Sync {
+    fn score(&self, candidate: &[u8]) -> f64;
+}
+
+/// Ad hoc strategies don't need a type of their own
+impl<F: Fn(&[u8]) -> f64 + Sync> Scorer for F {
+    fn score(&self, candidate: &[u8]) -> f64 {
+        self(candidate)
+    }
+}
+
+/// `text_score`, letter counts weighed by how common each letter is
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LetterFrequency {
+    pub language: Lang,
+}
+
+impl Scorer for LetterFrequency {
+    fn score(&self, candidate: &[u8]) -> f64 {
+        text_score(&String::from_utf8_lossy(candidate), self.language)
+    }
+}
+
+/// The chi-squared statistic per byte, negated. Empty input can't be judged and scores worst.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ChiSquared {
+    pub language: Lang,
+}
+
+impl Scorer for ChiSquared {
+    fn score(&self, candidate: &[u8]) -> f64 {
+        match candidate.is_empty() {
+            true => f64::NEG_INFINITY,
+            false => -chi2_test(candidate, self.language).per_byte,
+        }
+    }
+}
+
+/// Share of bytes that are printable ASCII or ordinary whitespace, from 0 to 1
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PrintableRatio;
+
+impl Scorer for PrintableRatio {
+    fn score(&self, candidate: &[u8]) -> f64 {
+        if candidate.is_empty() {
+            return 0.0;
+        }
+        let printable = candidate
+            .iter()
+            .filter(|&&byte| matches!(byte, 0x20..=0x7E | b'\t' | b'\n' | b'\r'))
+            .count();
+        printable as f64 / candidate.len() as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lib::analysis::{ByteLikelihood, NgramModel};
+
+    const GOOD: &[u8] = b"Cooking MC's like a pound of bacon";
+    /// A wrong guess at the key, control bytes and all
+    const BAD: &[u8] = b"Ieeacdm*GI-y*fcao*k*zeku\x0b\x1e";
+
+    #[test]
+    fn higher_should_be_better_for_every_scorer() {
+        let scorers: [&dyn Scorer; 5] = [
+            &LetterFrequency::default(),
+            &ChiSquared::default(),
+            &PrintableRatio,
+            NgramModel::english(4),
+            &ByteLikelihood::default(),
+        ];
+        for scorer in scorers {
+            assert!(scorer.score(GOOD) > scorer.score(BAD));
+        }
+        assert!(PrintableRatio.score(b"abc\x00") < PrintableRatio.score(b"abcd"));
+    }
+
+    #[test]
+    fn chi_squared_should_judge_anything_but_nothing() {
+        assert!(ChiSquared::default().score(b"1234!").is_finite());
+        assert!(ChiSquared::default().score(b"1234!") < ChiSquared::default().score(GOOD));
+        assert_eq!(ChiSquared::default().score(b""), f64::NEG_INFINITY);
+        assert_eq!(PrintableRatio.score(b""), 0.0);
+    }
+
+    #[test]
+    fn should_score_against_the_chosen_language() {
+        let german = "Die Katze schläft auf dem Sofa und der Hund wartet vor der Tür";
+        let chi = |language| ChiSquared { language }.score(german.as_bytes());
+        assert!(chi(Lang::German) > chi(Lang::English));
+
+        // umlauts are letters in German, hardly anything in English
+        let letters = |language| LetterFrequency { language }.score("äöü".as_bytes());
+        assert!(letters(Lang::German) > 10.0 * letters(Lang::English));
+    }
+
+    #[test]
+    fn closures_should_be_scorers() {
+        let shortest = |candidate: &[u8]| -(candidate.len() as f64);
+        assert_eq!(shortest.score(b"abc"), -3.0);
+    }
+}
@@ -1,5 +1,246 @@
+use std::fmt;
+
+use bimap::BiHashMap;
+
+use super::ConversionError;
+
 pub trait Alphabet {
     fn get_char_for_index(&self, index: u8) -> Option<char>;
     fn get_index_for_char(&self, character: char) -> Option<u8>;
-    fn get_padding_char(&self) -> char;
+    /// What short final groups are padded with, for alphabets that pad at all
+    fn get_padding_char(&self) -> Option<char> {
+        None
+    }
+}
+
+/// Make sure `alphabet` has exactly `count` symbols before a codec that
+/// needs that many indexes into it. Only the ends are looked at, the
+/// indices in between are assumed to be there.
+pub fn check_symbol_count<T: Alphabet>(alphabet: &T, count: u8) -> Result<(), ConversionError> {
+    let complete = alphabet.get_char_for_index(count - 1).is_some()
+        && alphabet.get_char_for_index(count).is_none();
+    match complete {
+        true => Ok(()),
+        false => Err(ConversionError::WrongAlphabetSize {
+            needed: count as usize,
+        }),
+    }
+}
+
+/// Symbol counts some codec in `conversion` knows what to do with: every
+/// power-of-two radix from binary to base64, plus base58 and base85
+const SUPPORTED_LENGTHS: [usize; 8] = [2, 4, 8, 16, 32, 58, 64, 85];
+
+/// Why a set of symbols can't make an alphabet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlphabetError {
+    /// No codec takes this many symbols
+    UnsupportedLength(usize),
+    /// `ch` shows up again at `index`, so decoding it would be ambiguous
+    DuplicateSymbol { index: usize, ch: char },
+    /// The padding character is also one of the symbols
+    PaddingIsSymbol(char),
+}
+
+impl fmt::Display for AlphabetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AlphabetError::UnsupportedLength(length) => {
+                write!(f, "no encoding uses {} symbols", length)
+            }
+            AlphabetError::DuplicateSymbol { index, ch } => {
+                write!(f, "symbol {:?} repeated at index {}", ch, index)
+            }
+            AlphabetError::PaddingIsSymbol(ch) => {
+                write!(f, "padding {:?} is also a symbol", ch)
+            }
+        }
+    }
+}
+
+impl std::error::Error for AlphabetError {}
+
+/// An alphabet put together at runtime, e.g. the shuffled base64 a CTF
+/// challenge hides its flag behind. Any `*_using_alphabet` function takes
+/// it, and returns `ConversionError::WrongAlphabetSize` unless it has the
+/// exact number of symbols that codec needs.
+#[derive(Debug, Clone)]
+pub struct CustomAlphabet {
+    symbols: BiHashMap<u8, char>,
+    padding: Option<char>,
+}
+
+impl CustomAlphabet {
+    /// `symbols` in index order, each exactly once, and a `padding` that isn't one of them.
+    /// Without padding, only the unpadded codecs work; padded ones return
+    /// `ConversionError::NoPaddingCharacter`.
+    pub fn new(symbols: &str, padding: Option<char>) -> Result<CustomAlphabet, AlphabetError> {
+        let length = symbols.chars().count();
+        if !SUPPORTED_LENGTHS.contains(&length) {
+            return Err(AlphabetError::UnsupportedLength(length));
+        }
+
+        let mut table = BiHashMap::with_capacity(length);
+        for (index, ch) in symbols.chars().enumerate() {
+            if Some(ch) == padding {
+                return Err(AlphabetError::PaddingIsSymbol(ch));
+            }
+            table
+                .insert_no_overwrite(index as u8, ch)
+                .map_err(|_| AlphabetError::DuplicateSymbol { index, ch })?;
+        }
+
+        Ok(CustomAlphabet {
+            symbols: table,
+            padding,
+        })
+    }
+
+    pub fn symbol_count(&self) -> usize {
+        self.symbols.len()
+    }
+}
+
+impl Alphabet for CustomAlphabet {
+    fn get_char_for_index(&self, index: u8) -> Option<char> {
+        self.symbols.get_by_left(&index).copied()
+    }
+
+    fn get_index_for_char(&self, character: char) -> Option<u8> {
+        self.symbols.get_by_right(&character).copied()
+    }
+
+    fn get_padding_char(&self) -> Option<char> {
+        self.padding
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lib::conversion::radix::HEX;
+    use crate::lib::conversion::{base32, base58, base64, base85, hex, ConversionError};
+
+    const SHUFFLED_BASE64: &str =
+        "ZYXWVUTSRQPONMLKJIHGFEDCBAzyxwvutsrqponmlkjihgfedcba9876543210+/";
+
+    #[test]
+    fn should_validate_symbols() {
+        assert_eq!(
+            CustomAlphabet::new("0123456789", None).unwrap_err(),
+            AlphabetError::UnsupportedLength(10)
+        );
+        assert_eq!(
+            CustomAlphabet::new("0123456789abcdea", None).unwrap_err(),
+            AlphabetError::DuplicateSymbol { index: 15, ch: 'a' }
+        );
+        assert_eq!(
+            CustomAlphabet::new(SHUFFLED_BASE64, Some('+')).unwrap_err(),
+            AlphabetError::PaddingIsSymbol('+')
+        );
+        assert_eq!(CustomAlphabet::new("αβγδ", None).unwrap().symbol_count(), 4);
+    }
+
+    #[test]
+    fn should_decode_shuffled_base64() {
+        let alphabet = CustomAlphabet::new(SHUFFLED_BASE64, Some('=')).unwrap();
+        let data = b"flag{shuffled}";
+
+        // the same symbols as classic base64, each one swapped for its stand-in
+        let classic = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+        let expected: String = base64::encode(data)
+            .chars()
+            .map(|c| match classic.find(c) {
+                Some(index) => SHUFFLED_BASE64.as_bytes()[index] as char,
+                None => c,
+            })
+            .collect();
+
+        let encoded = base64::encode_using_alphabet(&alphabet, data).unwrap();
+        assert_eq!(encoded, expected);
+        assert_eq!(
+            base64::decode_using_alphabet(&alphabet, &encoded).unwrap(),
+            data
+        );
+    }
+
+    #[test]
+    fn should_work_without_padding_when_unpadded() {
+        let alphabet = CustomAlphabet::new(SHUFFLED_BASE64, None).unwrap();
+        let data = b"hi";
+
+        let encoded = base64::encode_unpadded_using_alphabet(&alphabet, data).unwrap();
+        assert_eq!(encoded.chars().count(), 3);
+        assert_eq!(
+            base64::decode_unpadded_using_alphabet(&alphabet, &encoded).unwrap(),
+            data
+        );
+        assert_eq!(
+            base64::encode_using_alphabet(&alphabet, data),
+            Err(ConversionError::NoPaddingCharacter)
+        );
+        assert_eq!(
+            base64::decode_using_alphabet(&alphabet, &encoded),
+            Err(ConversionError::NoPaddingCharacter)
+        );
+    }
+
+    #[test]
+    fn should_refuse_codecs_needing_more_symbols() {
+        let digits = CustomAlphabet::new("0123456789abcdef", Some('=')).unwrap();
+        let data = b"\xff\xff\xff";
+
+        assert_eq!(
+            base64::encode_using_alphabet(&digits, data),
+            Err(ConversionError::WrongAlphabetSize { needed: 64 })
+        );
+        assert_eq!(
+            base64::decode_using_alphabet(&digits, "ffff"),
+            Err(ConversionError::WrongAlphabetSize { needed: 64 })
+        );
+        assert_eq!(
+            base58::encode_using_alphabet(&digits, data),
+            Err(ConversionError::WrongAlphabetSize { needed: 58 })
+        );
+        assert_eq!(
+            base58::decode_using_alphabet(&digits, "ffff"),
+            Err(ConversionError::WrongAlphabetSize { needed: 58 })
+        );
+        assert_eq!(
+            base85::encode_using_alphabet(&digits, data),
+            Err(ConversionError::WrongAlphabetSize { needed: 85 })
+        );
+        assert_eq!(
+            base85::decode_using_alphabet(&digits, "fffff"),
+            Err(ConversionError::WrongAlphabetSize { needed: 85 })
+        );
+    }
+
+    #[test]
+    fn should_work_with_every_codec() {
+        let data = b"\x00\x00custom alphabets";
+
+        let crockford = CustomAlphabet::new("0123456789ABCDEFGHJKMNPQRSTVWXYZ", Some('=')).unwrap();
+        let encoded = base32::encode_using_alphabet(&crockford, data).unwrap();
+        assert_eq!(
+            base32::decode_using_alphabet(&crockford, &encoded).unwrap(),
+            data
+        );
+
+        let bitcoin = CustomAlphabet::new(
+            "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz",
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            base58::encode_using_alphabet(&bitcoin, data).unwrap(),
+            base58::encode(data)
+        );
+
+        let digits = CustomAlphabet::new("0123456789abcdef", None).unwrap();
+        assert_eq!(
+            HEX.encode(&digits, data, false).unwrap(),
+            hex::encode_lowercase(data)
+        );
+    }
 }
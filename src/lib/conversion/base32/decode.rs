@@ -0,0 +1,101 @@
+use super::model::{ClassicBase32, ExtendedHexBase32};
+use crate::lib::conversion::alphabet::Alphabet;
+use crate::lib::conversion::radix::BASE32;
+use crate::lib::conversion::ConversionError;
+
+pub fn decode(data: &str) -> Result<Vec<u8>, ConversionError> {
+    let classic_alphabet = &ClassicBase32 {};
+    decode_using_alphabet(classic_alphabet, data)
+}
+
+/// Decode input that was encoded without trailing `=` padding.
+pub fn decode_unpadded(data: &str) -> Result<Vec<u8>, ConversionError> {
+    let classic_alphabet = &ClassicBase32 {};
+    decode_unpadded_using_alphabet(classic_alphabet, data)
+}
+
+/// Decode input using the "extended hex" alphabet (RFC 4648 §7).
+pub fn decode_extended_hex(data: &str) -> Result<Vec<u8>, ConversionError> {
+    let extended_hex_alphabet = &ExtendedHexBase32 {};
+    decode_using_alphabet(extended_hex_alphabet, data)
+}
+
+pub fn decode_using_alphabet<T: Alphabet>(
+    alphabet: &T,
+    data: &str,
+) -> Result<Vec<u8>, ConversionError> {
+    BASE32.decode(alphabet, data, true)
+}
+
+pub fn decode_unpadded_using_alphabet<T: Alphabet>(
+    alphabet: &T,
+    data: &str,
+) -> Result<Vec<u8>, ConversionError> {
+    BASE32.decode(alphabet, data, false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_decode_rfc_vectors() {
+        assert_eq!(decode("").unwrap(), b"");
+        assert_eq!(decode("MY======").unwrap(), b"f");
+        assert_eq!(decode("MZXQ====").unwrap(), b"fo");
+        assert_eq!(decode("MZXW6===").unwrap(), b"foo");
+        assert_eq!(decode("MZXW6YQ=").unwrap(), b"foob");
+        assert_eq!(decode("MZXW6YTB").unwrap(), b"fooba");
+        assert_eq!(decode("MZXW6YTBOI======").unwrap(), b"foobar");
+    }
+
+    #[test]
+    fn should_decode_extended_hex_rfc_vectors() {
+        assert_eq!(decode_extended_hex("CO======").unwrap(), b"f");
+        assert_eq!(decode_extended_hex("CPNMUOG=").unwrap(), b"foob");
+        assert_eq!(decode_extended_hex("CPNMUOJ1E8======").unwrap(), b"foobar");
+    }
+
+    #[test]
+    fn should_decode_lowercase_totp_secret() {
+        assert_eq!(
+            decode_unpadded("jbswy3dpehpk3pxp").unwrap(),
+            b"Hello!\xDE\xAD\xBE\xEF"
+        );
+    }
+
+    #[test]
+    fn should_keep_trailing_zeroes() {
+        assert_eq!(decode("AAAAAAAA").unwrap(), vec![0; 5]);
+        assert_eq!(decode("AA======").unwrap(), vec![0]);
+        assert_eq!(decode_unpadded("AAAAAAA").unwrap(), vec![0; 4]);
+    }
+
+    #[test]
+    fn should_report_where_it_broke() {
+        assert_eq!(
+            decode("MZXW6YQ").unwrap_err(),
+            ConversionError::InvalidLength
+        );
+        assert_eq!(
+            decode_unpadded("MZX").unwrap_err(),
+            ConversionError::InvalidLength
+        );
+        assert_eq!(
+            decode("MZXW6Y1=").unwrap_err(),
+            ConversionError::InvalidCharacter { index: 6, ch: '1' }
+        );
+        assert_eq!(
+            decode("MZX=====").unwrap_err(),
+            ConversionError::InvalidPadding
+        );
+        assert_eq!(
+            decode("MY======MY======").unwrap_err(),
+            ConversionError::InvalidPadding
+        );
+        assert_eq!(
+            decode("MZ======").unwrap_err(),
+            ConversionError::TrailingBits
+        );
+    }
+}
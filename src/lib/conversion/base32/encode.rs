@@ -0,0 +1,76 @@
+use super::model::{ClassicBase32, ExtendedHexBase32};
+use crate::lib::conversion::alphabet::Alphabet;
+use crate::lib::conversion::radix::BASE32;
+use crate::lib::conversion::ConversionError;
+
+pub fn encode(data: &[u8]) -> String {
+    let classic_alphabet = &ClassicBase32 {};
+    encode_built_in(classic_alphabet, data, true)
+}
+
+/// Encode without trailing `=` padding, the usual shape of TOTP secrets.
+pub fn encode_unpadded(data: &[u8]) -> String {
+    let classic_alphabet = &ClassicBase32 {};
+    encode_built_in(classic_alphabet, data, false)
+}
+
+/// Encode with the "extended hex" alphabet (RFC 4648 §7).
+pub fn encode_extended_hex(data: &[u8]) -> String {
+    let extended_hex_alphabet = &ExtendedHexBase32 {};
+    encode_built_in(extended_hex_alphabet, data, true)
+}
+
+/// Fails if `alphabet` isn't 32 symbols, or has no padding character.
+pub fn encode_using_alphabet<T: Alphabet>(
+    alphabet: &T,
+    data: &[u8],
+) -> Result<String, ConversionError> {
+    BASE32.encode(alphabet, data, true)
+}
+
+/// Fails if `alphabet` isn't 32 symbols.
+pub fn encode_unpadded_using_alphabet<T: Alphabet>(
+    alphabet: &T,
+    data: &[u8],
+) -> Result<String, ConversionError> {
+    BASE32.encode(alphabet, data, false)
+}
+
+fn encode_built_in<T: Alphabet>(alphabet: &T, data: &[u8], padded: bool) -> String {
+    BASE32
+        .encode(alphabet, data, padded)
+        .expect("The RFC 4648 alphabets have 32 symbols and pad with `=`")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_encode_rfc_vectors() {
+        // RFC 4648 §10
+        assert_eq!(encode(b""), "");
+        assert_eq!(encode(b"f"), "MY======");
+        assert_eq!(encode(b"fo"), "MZXQ====");
+        assert_eq!(encode(b"foo"), "MZXW6===");
+        assert_eq!(encode(b"foob"), "MZXW6YQ=");
+        assert_eq!(encode(b"fooba"), "MZXW6YTB");
+        assert_eq!(encode(b"foobar"), "MZXW6YTBOI======");
+    }
+
+    #[test]
+    fn should_encode_extended_hex_rfc_vectors() {
+        assert_eq!(encode_extended_hex(b"f"), "CO======");
+        assert_eq!(encode_extended_hex(b"fo"), "CPNG====");
+        assert_eq!(encode_extended_hex(b"foo"), "CPNMU===");
+        assert_eq!(encode_extended_hex(b"foob"), "CPNMUOG=");
+        assert_eq!(encode_extended_hex(b"fooba"), "CPNMUOJ1");
+        assert_eq!(encode_extended_hex(b"foobar"), "CPNMUOJ1E8======");
+    }
+
+    #[test]
+    fn should_encode_without_padding() {
+        assert_eq!(encode_unpadded(b"foobar"), "MZXW6YTBOI");
+        assert_eq!(encode_unpadded(b"fooba"), "MZXW6YTB");
+    }
+}
@@ -0,0 +1,38 @@
+/*
+
+Base32 and Base32hex conversion, see RFC 4648 §6 and §7.
+Same shape as base64, just five bits a symbol and groups of eight.
+
+*/
+mod decode;
+mod encode;
+mod model;
+
+pub use decode::{
+    decode, decode_extended_hex, decode_unpadded, decode_unpadded_using_alphabet,
+    decode_using_alphabet,
+};
+pub use encode::{
+    encode, encode_extended_hex, encode_unpadded, encode_unpadded_using_alphabet,
+    encode_using_alphabet,
+};
+pub use model::{ClassicBase32, ExtendedHexBase32};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_convert_back_and_forth() {
+        let data: Vec<u8> = (0..=255_u8).rev().collect();
+        for length in 0..=data.len() {
+            let slice = &data[..length];
+            assert_eq!(decode(&encode(slice)).unwrap(), slice);
+            assert_eq!(decode_unpadded(&encode_unpadded(slice)).unwrap(), slice);
+            assert_eq!(
+                decode_extended_hex(&encode_extended_hex(slice)).unwrap(),
+                slice
+            );
+        }
+    }
+}
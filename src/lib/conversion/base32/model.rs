@@ -0,0 +1,117 @@
+use crate::lib::conversion::alphabet::Alphabet;
+
+const UPPERCASEOFFSET: u8 = 65;
+const LOWERCASEOFFSET: u8 = 97;
+const DIGITOFFSET: u8 = 50 - 26;
+
+const HEX_DIGITOFFSET: u8 = 48;
+const HEX_UPPERCASEOFFSET: u8 = 65 - 10;
+const HEX_LOWERCASEOFFSET: u8 = 97 - 10;
+
+/// The RFC 4648 §6 alphabet: A-Z then 2-7.
+pub struct ClassicBase32;
+
+impl Alphabet for ClassicBase32 {
+    fn get_char_for_index(&self, index: u8) -> Option<char> {
+        let ascii_index = match index {
+            0..=25 => index + UPPERCASEOFFSET, // A-Z
+            26..=31 => index + DIGITOFFSET,    // 2-7
+            _ => return None,
+        };
+
+        Some(ascii_index as char)
+    }
+
+    /// Lowercase is accepted too, TOTP secrets are often handed out that way
+    fn get_index_for_char(&self, character: char) -> Option<u8> {
+        if !character.is_ascii() {
+            return None;
+        }
+
+        let character = character as u8;
+        match character {
+            65..=90 => Some(character - UPPERCASEOFFSET),  // A-Z
+            97..=122 => Some(character - LOWERCASEOFFSET), // a-z
+            50..=55 => Some(character - DIGITOFFSET),      // 2-7
+            _ => None,
+        }
+    }
+
+    fn get_padding_char(&self) -> Option<char> {
+        Some('=')
+    }
+}
+
+/// The RFC 4648 §7 "extended hex" alphabet: 0-9 then A-V. Sorts the same
+/// way the raw bytes do, which is why DNSSEC uses it.
+pub struct ExtendedHexBase32;
+
+impl Alphabet for ExtendedHexBase32 {
+    fn get_char_for_index(&self, index: u8) -> Option<char> {
+        let ascii_index = match index {
+            0..=9 => index + HEX_DIGITOFFSET,       // 0-9
+            10..=31 => index + HEX_UPPERCASEOFFSET, // A-V
+            _ => return None,
+        };
+
+        Some(ascii_index as char)
+    }
+
+    fn get_index_for_char(&self, character: char) -> Option<u8> {
+        if !character.is_ascii() {
+            return None;
+        }
+
+        let character = character as u8;
+        match character {
+            48..=57 => Some(character - HEX_DIGITOFFSET),     // 0-9
+            65..=86 => Some(character - HEX_UPPERCASEOFFSET), // A-V
+            97..=118 => Some(character - HEX_LOWERCASEOFFSET), // a-v
+            _ => None,
+        }
+    }
+
+    fn get_padding_char(&self) -> Option<char> {
+        Some('=')
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_map_both_ways() {
+        let alphabet = ClassicBase32 {};
+        let symbols: String = (0..32)
+            .filter_map(|n| alphabet.get_char_for_index(n))
+            .collect();
+        assert_eq!(symbols, "ABCDEFGHIJKLMNOPQRSTUVWXYZ234567");
+
+        let indices: Vec<u8> = symbols
+            .chars()
+            .filter_map(|c| alphabet.get_index_for_char(c))
+            .collect();
+        assert_eq!(indices, (0..32).collect::<Vec<u8>>());
+        assert_eq!(alphabet.get_index_for_char('z'), Some(25));
+        assert_eq!(alphabet.get_index_for_char('1'), None);
+        assert_eq!(alphabet.get_index_for_char('8'), None);
+    }
+
+    #[test]
+    fn extended_hex_should_map_both_ways() {
+        let alphabet = ExtendedHexBase32 {};
+        let symbols: String = (0..32)
+            .filter_map(|n| alphabet.get_char_for_index(n))
+            .collect();
+        assert_eq!(symbols, "0123456789ABCDEFGHIJKLMNOPQRSTUV");
+
+        let indices: Vec<u8> = symbols
+            .chars()
+            .filter_map(|c| alphabet.get_index_for_char(c))
+            .collect();
+        assert_eq!(indices, (0..32).collect::<Vec<u8>>());
+        assert_eq!(alphabet.get_index_for_char('v'), Some(31));
+        assert_eq!(alphabet.get_index_for_char('W'), None);
+    }
+}
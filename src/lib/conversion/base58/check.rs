@@ -0,0 +1,102 @@
+use sha2::{Digest, Sha256};
+
+use super::decode::decode_using_alphabet;
+use super::encode::{encode, encode_using_alphabet};
+use super::model::BitcoinBase58;
+use crate::lib::conversion::alphabet::Alphabet;
+use crate::lib::conversion::ConversionError;
+
+const CHECKSUM_LENGTH: usize = 4;
+
+/// First four bytes of SHA256(SHA256(payload))
+fn checksum(payload: &[u8]) -> [u8; CHECKSUM_LENGTH] {
+    let digest = Sha256::digest(Sha256::digest(payload));
+    let mut out = [0; CHECKSUM_LENGTH];
+    out.copy_from_slice(&digest[..CHECKSUM_LENGTH]);
+    out
+}
+
+/// Base58Check: append a four byte checksum before encoding. Any version
+/// byte (0x00 for a Bitcoin address) is expected to lead `payload` already.
+pub fn encode_check(payload: &[u8]) -> String {
+    encode(&with_checksum(payload))
+}
+
+/// Decode Base58Check, verifying and then dropping the checksum.
+pub fn decode_check(data: &str) -> Result<Vec<u8>, ConversionError> {
+    let bitcoin_alphabet = &BitcoinBase58 {};
+    decode_check_using_alphabet(bitcoin_alphabet, data)
+}
+
+pub fn encode_check_using_alphabet<T: Alphabet>(
+    alphabet: &T,
+    payload: &[u8],
+) -> Result<String, ConversionError> {
+    encode_using_alphabet(alphabet, &with_checksum(payload))
+}
+
+fn with_checksum(payload: &[u8]) -> Vec<u8> {
+    let mut data = payload.to_vec();
+    data.extend_from_slice(&checksum(payload));
+    data
+}
+
+pub fn decode_check_using_alphabet<T: Alphabet>(
+    alphabet: &T,
+    data: &str,
+) -> Result<Vec<u8>, ConversionError> {
+    let mut decoded = decode_using_alphabet(alphabet, data)?;
+    if decoded.len() < CHECKSUM_LENGTH {
+        return Err(ConversionError::InvalidLength);
+    }
+
+    let expected = decoded.split_off(decoded.len() - CHECKSUM_LENGTH);
+    if expected != checksum(&decoded) {
+        return Err(ConversionError::InvalidChecksum);
+    }
+
+    Ok(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lib::conversion::base58::RippleBase58;
+    use crate::lib::conversion::hex;
+
+    #[test]
+    fn should_encode_bitcoin_addresses() {
+        assert_eq!(encode_check(&[0; 21]), "1111111111111111111114oLvT2");
+
+        // the genesis block's coinbase address
+        let payload = hex::decode("0062E907B15CBF27D5425399EBF6F0FB50EBB88F18").unwrap();
+        assert_eq!(encode_check(&payload), "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa");
+        assert_eq!(
+            decode_check("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa").unwrap(),
+            payload
+        );
+    }
+
+    #[test]
+    fn should_encode_ripple_account_zero() {
+        let ripple_alphabet = &RippleBase58 {};
+        let encoded = encode_check_using_alphabet(ripple_alphabet, &[0; 21]).unwrap();
+        assert_eq!(encoded, "rrrrrrrrrrrrrrrrrrrrrhoLvTp");
+        assert_eq!(
+            decode_check_using_alphabet(ripple_alphabet, &encoded).unwrap(),
+            vec![0; 21]
+        );
+    }
+
+    #[test]
+    fn should_catch_typos() {
+        assert_eq!(
+            decode_check("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNb").unwrap_err(),
+            ConversionError::InvalidChecksum
+        );
+        assert_eq!(
+            decode_check("1").unwrap_err(),
+            ConversionError::InvalidLength
+        );
+    }
+}
@@ -0,0 +1,69 @@
+use super::model::{BitcoinBase58, BASE};
+use crate::lib::conversion::alphabet::{check_symbol_count, Alphabet};
+use crate::lib::conversion::ConversionError;
+
+pub fn decode(data: &str) -> Result<Vec<u8>, ConversionError> {
+    let bitcoin_alphabet = &BitcoinBase58 {};
+    decode_using_alphabet(bitcoin_alphabet, data)
+}
+
+/// Fails if `alphabet` isn't exactly 58 symbols, as well as on bad input.
+pub fn decode_using_alphabet<T: Alphabet>(
+    alphabet: &T,
+    data: &str,
+) -> Result<Vec<u8>, ConversionError> {
+    check_symbol_count(alphabet, BASE as u8)?;
+    let zero = alphabet
+        .get_char_for_index(0)
+        .ok_or(ConversionError::WrongAlphabetSize { needed: BASE })?;
+    let zeroes = data.chars().take_while(|&c| c == zero).count();
+
+    // little-endian bytes, grown as the number does
+    let mut bytes: Vec<u8> = Vec::with_capacity(data.len() * 733 / 1000 + 1);
+    for (index, ch) in data.chars().enumerate().skip(zeroes) {
+        let mut carry = alphabet
+            .get_index_for_char(ch)
+            .ok_or(ConversionError::InvalidCharacter { index, ch })?
+            as usize;
+        for byte in bytes.iter_mut() {
+            carry += *byte as usize * BASE;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+
+    Ok(std::iter::repeat_n(0, zeroes)
+        .chain(bytes.into_iter().rev())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_decode_known_vectors() {
+        assert_eq!(decode("").unwrap(), b"");
+        assert_eq!(decode("2NEpo7TZRRrLZSi2U").unwrap(), b"Hello World!");
+        assert_eq!(
+            decode("11233QC4").unwrap(),
+            vec![0x00, 0x00, 0x28, 0x7F, 0xB4, 0xCD]
+        );
+    }
+
+    #[test]
+    fn should_fail_gracefully() {
+        assert_eq!(
+            decode("2NEpo0TZ").unwrap_err(),
+            ConversionError::InvalidCharacter { index: 5, ch: '0' }
+        );
+        assert_eq!(
+            decode("11l").unwrap_err(),
+            ConversionError::InvalidCharacter { index: 2, ch: 'l' }
+        );
+    }
+}
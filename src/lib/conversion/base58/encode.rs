@@ -0,0 +1,67 @@
+use super::model::{BitcoinBase58, BASE};
+use crate::lib::conversion::alphabet::{check_symbol_count, Alphabet};
+use crate::lib::conversion::ConversionError;
+
+pub fn encode(data: &[u8]) -> String {
+    let bitcoin_alphabet = &BitcoinBase58 {};
+    encode_using_alphabet(bitcoin_alphabet, data).expect("BitcoinBase58 has all 58 symbols")
+}
+
+/// Treat `data` as one big-endian number and write it out in base 58.
+/// Leading zero bytes would vanish from the number, so each becomes a zero symbol.
+/// Fails if `alphabet` isn't exactly 58 symbols.
+pub fn encode_using_alphabet<T: Alphabet>(
+    alphabet: &T,
+    data: &[u8],
+) -> Result<String, ConversionError> {
+    check_symbol_count(alphabet, BASE as u8)?;
+    let symbol = |digit: u8| {
+        alphabet
+            .get_char_for_index(digit)
+            .ok_or(ConversionError::WrongAlphabetSize { needed: BASE })
+    };
+
+    let zeroes = data.iter().take_while(|&&byte| byte == 0).count();
+
+    // little-endian base 58 digits, grown as the number does
+    let mut digits: Vec<u8> = Vec::with_capacity(data.len() * 138 / 100 + 1);
+    for &byte in &data[zeroes..] {
+        let mut carry = byte as usize;
+        for digit in digits.iter_mut() {
+            carry += (*digit as usize) << 8;
+            *digit = (carry % BASE) as u8;
+            carry /= BASE;
+        }
+        while carry > 0 {
+            digits.push((carry % BASE) as u8);
+            carry /= BASE;
+        }
+    }
+
+    let zero = symbol(0)?;
+    std::iter::repeat_n(Ok(zero), zeroes)
+        .chain(digits.iter().rev().map(|&digit| symbol(digit)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_encode_known_vectors() {
+        assert_eq!(encode(b""), "");
+        assert_eq!(encode(b"Hello World!"), "2NEpo7TZRRrLZSi2U");
+        assert_eq!(
+            encode(b"The quick brown fox jumps over the lazy dog."),
+            "USm3fpXnKG5EUBx2ndxBDMPVciP5hGey2Jh4NDv6gmeo1LkMeiKrLJUUBk6Z"
+        );
+    }
+
+    #[test]
+    fn should_keep_leading_zeroes() {
+        assert_eq!(encode(&[0x00, 0x00, 0x28, 0x7F, 0xB4, 0xCD]), "11233QC4");
+        assert_eq!(encode(&[0x00]), "1");
+        assert_eq!(encode(&[0x00, 0x00, 0x00]), "111");
+    }
+}
@@ -0,0 +1,66 @@
+/*
+
+Base58 and Base58Check. Unlike the power-of-two bases this one has no
+bit groups to lean on: the whole input is one big number, converted by
+repeated division, with leading zero bytes carried over as zero symbols.
+
+*/
+mod check;
+mod decode;
+mod encode;
+mod model;
+
+pub use check::{
+    decode_check, decode_check_using_alphabet, encode_check, encode_check_using_alphabet,
+};
+pub use decode::{decode, decode_using_alphabet};
+pub use encode::{encode, encode_using_alphabet};
+pub use model::{BitcoinBase58, FlickrBase58, RippleBase58};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lib::conversion::base64::ClassicBase64;
+    use crate::lib::conversion::hex::ClassicHex;
+    use crate::lib::conversion::ConversionError;
+
+    #[test]
+    fn should_convert_back_and_forth() {
+        let data: Vec<u8> = (0..=255_u8).collect();
+        for length in 0..=64 {
+            let slice = &data[..length];
+            assert_eq!(decode(&encode(slice)).unwrap(), slice);
+            assert_eq!(decode_check(&encode_check(slice)).unwrap(), slice);
+        }
+    }
+
+    #[test]
+    fn should_convert_back_and_forth_with_other_alphabets() {
+        let data = vec![0x00, 0xD0, 0x0D, 0xCA, 0xFE];
+        let flickr = encode_using_alphabet(&FlickrBase58 {}, &data).unwrap();
+        assert_eq!(
+            decode_using_alphabet(&FlickrBase58 {}, &flickr).unwrap(),
+            data
+        );
+        let ripple = encode_using_alphabet(&RippleBase58 {}, &data).unwrap();
+        assert!(ripple.starts_with('r'));
+        assert_eq!(
+            decode_using_alphabet(&RippleBase58 {}, &ripple).unwrap(),
+            data
+        );
+    }
+
+    #[test]
+    fn should_need_exactly_58_symbols() {
+        let too_few = encode_using_alphabet(&ClassicHex {}, b"\xff\xff\xff");
+        assert_eq!(
+            too_few,
+            Err(ConversionError::WrongAlphabetSize { needed: 58 })
+        );
+        // every base58 string is valid base64 too, but the digits would be wrong
+        assert_eq!(
+            decode_using_alphabet(&ClassicBase64 {}, "2NEpo7TZRRrLZSi2U"),
+            Err(ConversionError::WrongAlphabetSize { needed: 58 })
+        );
+    }
+}
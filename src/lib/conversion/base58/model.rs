@@ -0,0 +1,87 @@
+use crate::lib::conversion::alphabet::Alphabet;
+
+/*
+    Base58 drops the look-alikes 0, O, I and l from the alphanumerics. Every
+    alphabet is just an ordering of 58 symbols, so they share the lookup.
+*/
+const BITCOIN: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+const RIPPLE: &str = "rpshnaf39wBUDNEGHJKLM4PQRST7VWXYZ2bcdeCg65jkm8oFqi1tuvAxyz";
+const FLICKR: &str = "123456789abcdefghijkmnopqrstuvwxyzABCDEFGHJKLMNPQRSTUVWXYZ";
+
+pub const BASE: usize = 58;
+
+fn char_for_index(symbols: &str, index: u8) -> Option<char> {
+    symbols.as_bytes().get(index as usize).map(|&b| b as char)
+}
+
+fn index_for_char(symbols: &str, character: char) -> Option<u8> {
+    symbols
+        .chars()
+        .position(|c| c == character)
+        .map(|i| i as u8)
+}
+
+/// The alphabet of Bitcoin addresses and IPFS hashes
+pub struct BitcoinBase58;
+
+impl Alphabet for BitcoinBase58 {
+    fn get_char_for_index(&self, index: u8) -> Option<char> {
+        char_for_index(BITCOIN, index)
+    }
+
+    fn get_index_for_char(&self, character: char) -> Option<u8> {
+        index_for_char(BITCOIN, character)
+    }
+}
+
+/// Ripple (XRP) account addresses
+pub struct RippleBase58;
+
+impl Alphabet for RippleBase58 {
+    fn get_char_for_index(&self, index: u8) -> Option<char> {
+        char_for_index(RIPPLE, index)
+    }
+
+    fn get_index_for_char(&self, character: char) -> Option<u8> {
+        index_for_char(RIPPLE, character)
+    }
+}
+
+/// Flickr short URLs, the Bitcoin alphabet with the cases swapped
+pub struct FlickrBase58;
+
+impl Alphabet for FlickrBase58 {
+    fn get_char_for_index(&self, index: u8) -> Option<char> {
+        char_for_index(FLICKR, index)
+    }
+
+    fn get_index_for_char(&self, character: char) -> Option<u8> {
+        index_for_char(FLICKR, character)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_have_58_distinct_symbols() {
+        for symbols in [BITCOIN, RIPPLE, FLICKR] {
+            let mut sorted: Vec<char> = symbols.chars().collect();
+            sorted.sort_unstable();
+            sorted.dedup();
+            assert_eq!(sorted.len(), BASE);
+        }
+    }
+
+    #[test]
+    fn should_map_both_ways() {
+        let alphabet = BitcoinBase58 {};
+        assert_eq!(alphabet.get_char_for_index(0), Some('1'));
+        assert_eq!(alphabet.get_char_for_index(57), Some('z'));
+        assert_eq!(alphabet.get_char_for_index(58), None);
+        assert_eq!(alphabet.get_index_for_char('z'), Some(57));
+        assert_eq!(alphabet.get_index_for_char('0'), None);
+        assert_eq!(alphabet.get_index_for_char('l'), None);
+    }
+}
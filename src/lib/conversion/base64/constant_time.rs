@@ -0,0 +1,178 @@
+/*
+
+Base64 for secret material, after the approach libsodium takes. Symbols
+are mapped to and from their index with range masks instead of matches or
+table lookups, so timing says nothing about the data. Padding depends only
+on the length, which isn't secret. A bad input is only detected once the
+whole of it has been processed, and then goes to the regular decoder so
+the error matches.
+
+*/
+use super::decode::{decode, decode_url_safe};
+use crate::lib::conversion::ConversionError;
+
+/// The two symbols that differ between the classic and URL-safe alphabets
+#[derive(Clone, Copy)]
+struct LastSymbols(i16, i16);
+
+const CLASSIC: LastSymbols = LastSymbols(b'+' as i16, b'/' as i16);
+const URL_SAFE: LastSymbols = LastSymbols(b'-' as i16, b'_' as i16);
+
+pub fn encode_constant_time(data: &[u8]) -> String {
+    encode_using_symbols(data, CLASSIC)
+}
+
+pub fn encode_url_safe_constant_time(data: &[u8]) -> String {
+    encode_using_symbols(data, URL_SAFE)
+}
+
+pub fn decode_constant_time(data: &str) -> Result<Vec<u8>, ConversionError> {
+    decode_using_symbols(data, CLASSIC).map_or_else(|| decode(data), Ok)
+}
+
+pub fn decode_url_safe_constant_time(data: &str) -> Result<Vec<u8>, ConversionError> {
+    decode_using_symbols(data, URL_SAFE).map_or_else(|| decode_url_safe(data), Ok)
+}
+
+fn encode_using_symbols(data: &[u8], last: LastSymbols) -> String {
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0_u32, |group, (i, &byte)| {
+            group | (byte as u32) << (16 - 8 * i)
+        });
+
+        for i in 0..=chunk.len() {
+            let index = (group >> (18 - 6 * i)) & 0x3F;
+            out.push(encode_symbol(index as i16, last) as char);
+        }
+        (chunk.len()..3).for_each(|_| out.push('='));
+    }
+    out
+}
+
+/// `None` for any input the regular decoder would refuse
+fn decode_using_symbols(data: &str, last: LastSymbols) -> Option<Vec<u8>> {
+    let bytes = data.as_bytes();
+    if !bytes.len().is_multiple_of(4) {
+        return None;
+    }
+
+    let padding = bytes
+        .iter()
+        .rev()
+        .take(2)
+        .take_while(|&&b| b == b'=')
+        .count();
+    let symbols = &bytes[..bytes.len() - padding];
+    if symbols.len() % 4 == 1 {
+        return None;
+    }
+
+    let mut out = Vec::with_capacity(symbols.len() / 4 * 3 + 2);
+    let mut invalid: i16 = 0;
+    for chunk in symbols.chunks(4) {
+        let mut group = 0_u32;
+        for (i, &character) in chunk.iter().enumerate() {
+            let index = decode_symbol(character, last);
+            invalid |= index;
+            group |= (index as u32 & 0x3F) << (18 - 6 * i);
+        }
+
+        let bytes = chunk.len() * 6 / 8;
+        out.extend_from_slice(&group.to_be_bytes()[1..1 + bytes]);
+
+        // bits past the last byte must be zero, as an encoder leaves them
+        let filler = chunk.len() * 6 - bytes * 8;
+        let leftover = (group >> (24 - 6 * chunk.len())) & ((1 << filler) - 1);
+        invalid |= -(leftover as i16);
+    }
+
+    // every failure above left the sign bit set
+    (invalid >= 0).then_some(out)
+}
+
+/// Start at `A` and shift by mask as the index crosses into each later range
+fn encode_symbol(index: i16, last: LastSymbols) -> u8 {
+    let mut symbol = index + b'A' as i16;
+    symbol += (25 - index) >> 8 & 6; // a-z
+    symbol -= (51 - index) >> 8 & 75; // 0-9
+    symbol += (61 - index) >> 8 & (last.0 - b':' as i16);
+    symbol += (62 - index) >> 8 & (last.1 - last.0 - 1);
+    symbol as u8
+}
+
+/// The symbol's index, or -1. `(lo - 1 - c) & (c - hi - 1)` is negative exactly
+/// when `lo <= c <= hi`, and shifting that down gives an all-ones mask.
+fn decode_symbol(character: u8, last: LastSymbols) -> i16 {
+    let c = character as i16;
+    let mut index: i16 = -1;
+    index += ((0x40 - c) & (c - 0x5B)) >> 8 & (c - 0x40); // A-Z
+    index += ((0x60 - c) & (c - 0x7B)) >> 8 & (c - 0x46); // a-z
+    index += ((0x2F - c) & (c - 0x3A)) >> 8 & (c + 0x05); // 0-9
+    index += ((last.0 - 1 - c) & (c - last.0 - 1)) >> 8 & 63;
+    index += ((last.1 - 1 - c) & (c - last.1 - 1)) >> 8 & 64;
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lib::conversion::alphabet::Alphabet;
+    use crate::lib::conversion::base64::{encode, encode_url_safe, ClassicBase64, UrlSafeBase64};
+
+    #[test]
+    fn should_agree_with_the_alphabets_on_every_input() {
+        for index in 0..64 {
+            let classic = ClassicBase64 {}.get_char_for_index(index);
+            let url_safe = UrlSafeBase64 {}.get_char_for_index(index);
+            assert_eq!(Some(encode_symbol(index as i16, CLASSIC) as char), classic);
+            assert_eq!(
+                Some(encode_symbol(index as i16, URL_SAFE) as char),
+                url_safe
+            );
+        }
+        for byte in 0..=255_u8 {
+            let classic = ClassicBase64 {}.get_index_for_char(byte as char);
+            let url_safe = UrlSafeBase64 {}.get_index_for_char(byte as char);
+            assert_eq!(decode_symbol(byte, CLASSIC), classic.map_or(-1, i16::from));
+            assert_eq!(
+                decode_symbol(byte, URL_SAFE),
+                url_safe.map_or(-1, i16::from)
+            );
+        }
+    }
+
+    #[test]
+    fn should_agree_with_the_regular_codec() {
+        let data: Vec<u8> = (0..=255_u8).rev().collect();
+        for length in 0..=data.len() {
+            let slice = &data[..length];
+            let encoded = encode_constant_time(slice);
+            assert_eq!(encoded, encode(slice));
+            assert_eq!(decode_constant_time(&encoded).unwrap(), slice);
+
+            let encoded = encode_url_safe_constant_time(slice);
+            assert_eq!(encoded, encode_url_safe(slice));
+            assert_eq!(decode_url_safe_constant_time(&encoded).unwrap(), slice);
+        }
+    }
+
+    #[test]
+    fn should_fail_the_same_way() {
+        let inputs = [
+            "YQ",
+            "YQ=",
+            "Y===",
+            "YQ=A",
+            "YQ==YQ==",
+            "YR==",
+            "YWJ=",
+            "YW!j",
+            "YWJjYW\u{e9}=",
+            "-_-_",
+        ];
+        for input in inputs {
+            assert_eq!(decode_constant_time(input), decode(input), "{}", input);
+        }
+    }
+}
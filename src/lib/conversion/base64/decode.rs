@@ -1,88 +1,98 @@
-use std::io;
-
-use super::model::ClassicBase64;
+use super::fast;
+use super::model::{ClassicBase64, CLASSIC_BASE64_TABLE, URL_SAFE_BASE64_TABLE};
 use crate::lib::conversion::alphabet::Alphabet;
+use crate::lib::conversion::radix::BASE64;
+use crate::lib::conversion::ConversionError;
+
+pub fn decode(data: &str) -> Result<Vec<u8>, ConversionError> {
+    fast::decode(&CLASSIC_BASE64_TABLE, data, true)
+}
+
+/// Decode input that was encoded without trailing `=` padding.
+pub fn decode_unpadded(data: &str) -> Result<Vec<u8>, ConversionError> {
+    fast::decode(&CLASSIC_BASE64_TABLE, data, false)
+}
+
+/// Decode input using the URL and filename safe alphabet (RFC 4648 §5).
+pub fn decode_url_safe(data: &str) -> Result<Vec<u8>, ConversionError> {
+    fast::decode(&URL_SAFE_BASE64_TABLE, data, true)
+}
+
+/// Decode URL-safe input without padding, as found in JWTs.
+pub fn decode_url_safe_unpadded(data: &str) -> Result<Vec<u8>, ConversionError> {
+    fast::decode(&URL_SAFE_BASE64_TABLE, data, false)
+}
 
-pub fn decode(data: &str) -> Result<Vec<u8>, std::io::Error> {
+/// Decode line-wrapped (MIME/PEM style) input, skipping any ASCII whitespace.
+pub fn decode_lenient(data: &str) -> Result<Vec<u8>, ConversionError> {
     let classic_alphabet = &ClassicBase64 {};
-    decode_using_alphabet(classic_alphabet, data)
+    decode_lenient_using_alphabet(classic_alphabet, data)
+}
+
+/// Bytes that decoding `data` will produce, whether it's padded or not
+pub fn decoded_len(data: &str) -> Result<usize, ConversionError> {
+    BASE64.decoded_len(&ClassicBase64 {}, data)
+}
+
+/// Decode into the front of `out` without allocating, returning the bytes written.
+pub fn decode_to_slice(data: &str, out: &mut [u8]) -> Result<usize, ConversionError> {
+    let classic_alphabet = &ClassicBase64 {};
+    decode_to_slice_using_alphabet(classic_alphabet, data, out)
 }
 
 pub fn decode_using_alphabet<T: Alphabet>(
     alphabet: &T,
     data: &str,
-) -> Result<Vec<u8>, std::io::Error> {
-    if data.chars().count() % 4 != 0 {
-        return Err(io::Error::from(io::ErrorKind::InvalidInput));
-    }
-
-    let mut result: Vec<_> = data
-        .chars()
-        .collect::<Vec<char>>()
-        .chunks(4)
-        .map(|chunk| original(alphabet, chunk))
-        .flat_map(stitch)
-        .collect();
-
-    /*
-        Only strip ending zeroes AFTER all chunks are together.
-    */
-
-    let final_zeroes_index = result
-        .iter()
-        .enumerate()
-        .rev()
-        .find(|(_, &el)| el != 0x0000)
-        .map(|(idx, _)| idx + 1)
-        .unwrap_or_else(|| result.len());
-    result.drain(final_zeroes_index..);
+) -> Result<Vec<u8>, ConversionError> {
+    BASE64.decode(alphabet, data, true)
+}
 
-    Ok(result)
+pub fn decode_unpadded_using_alphabet<T: Alphabet>(
+    alphabet: &T,
+    data: &str,
+) -> Result<Vec<u8>, ConversionError> {
+    BASE64.decode(alphabet, data, false)
 }
 
-fn original<T: Alphabet>(alphabet: &T, chunk: &[char]) -> Vec<u8> {
-    chunk
-        .iter()
-        .filter(|&&character| character != alphabet.get_padding_char())
-        .map(|&character| {
-            alphabet
-                .get_index_for_char(character)
-                .expect("Unable to find character in alphabet")
-        })
-        .collect()
+pub fn decode_to_slice_using_alphabet<T: Alphabet>(
+    alphabet: &T,
+    data: &str,
+    out: &mut [u8],
+) -> Result<usize, ConversionError> {
+    BASE64.decode_to_slice(alphabet, data, true, out)
 }
 
-/// Turn an array of six-byte clusters into proper u8s
-fn stitch(bytes: Vec<u8>) -> Vec<u8> {
-    let length = bytes.len();
-    let out = match length {
-        2 => vec![
-            // six bytes from first + two bytes from second
-            (bytes[0] & 0b00111111) << 2 | bytes[1] >> 4,
-            // remaining four bytes
-            (bytes[1] & 0b00001111) << 4,
-        ],
-        3 => vec![
-            // six bytes from first + two bytes from second
-            (bytes[0] & 0b00111111) << 2 | bytes[1] >> 4,
-            // four bytes from second, four bytes from third
-            (bytes[1] & 0b00001111) << 4 | bytes[2] >> 2,
-            // remaining four bytes
-            (bytes[2] & 0b00000011) << 6,
-        ],
-        4 => vec![
-            // six bytes from first + two bytes from second
-            (bytes[0] & 0b00111111) << 2 | bytes[1] >> 4,
-            // four bytes from second, four bytes from third
-            (bytes[1] & 0b00001111) << 4 | bytes[2] >> 2,
-            // remaining two bytes + all six bytes in third
-            (bytes[2] & 0b00000011) << 6 | bytes[3] & 0b00111111,
-        ],
-
-        _ => unreachable!(),
-    };
-
-    out.into_iter().collect()
+pub fn decode_lenient_using_alphabet<T: Alphabet>(
+    alphabet: &T,
+    data: &str,
+) -> Result<Vec<u8>, ConversionError> {
+    let is_content = |character: &char| !character.is_ascii_whitespace();
+    let length = data.chars().filter(is_content).count();
+
+    let mut result = Vec::with_capacity(length / 4 * 3);
+    BASE64
+        .decode_with(
+            alphabet,
+            data.chars().filter(is_content),
+            length,
+            true,
+            |byte| result.push(byte),
+        )
+        .map_err(|error| match error {
+            // point back into the original input, line breaks and all
+            ConversionError::InvalidCharacter { index, ch } => ConversionError::InvalidCharacter {
+                index: data
+                    .chars()
+                    .enumerate()
+                    .filter(|(_, character)| is_content(character))
+                    .nth(index)
+                    .map_or(index, |(position, _)| position),
+                ch,
+            },
+            _ => error,
+        })?;
+
+    Ok(result)
 }
 
 #[cfg(test)]
@@ -90,7 +100,6 @@ mod tests {
     use itertools::Itertools;
 
     use super::*;
-    use std::io;
 
     #[test]
     fn decode_one() {
@@ -122,11 +131,75 @@ mod tests {
         let encoded = String::from("d91jd");
         assert!(decode(&encoded).is_err());
         assert_eq!(
-            decode(&encoded).unwrap_err().kind(),
-            io::ErrorKind::InvalidInput
+            decode(&encoded).unwrap_err(),
+            ConversionError::InvalidLength
+        );
+    }
+
+    #[test]
+    fn should_report_invalid_character_position() {
+        assert_eq!(
+            decode("YW!j").unwrap_err(),
+            ConversionError::InvalidCharacter { index: 2, ch: '!' }
+        );
+        assert_eq!(
+            decode("YWJjYWé=").unwrap_err(),
+            ConversionError::InvalidCharacter { index: 6, ch: 'é' }
+        );
+        assert_eq!(
+            decode_url_safe("+/+/").unwrap_err(),
+            ConversionError::InvalidCharacter { index: 0, ch: '+' }
         );
     }
 
+    #[test]
+    fn should_reject_misplaced_padding() {
+        assert_eq!(
+            decode("YQ==YQ==").unwrap_err(),
+            ConversionError::InvalidPadding
+        );
+        assert_eq!(decode("YQ=A").unwrap_err(), ConversionError::InvalidPadding);
+        assert_eq!(decode("====").unwrap_err(), ConversionError::InvalidPadding);
+        assert_eq!(
+            decode_unpadded("YQ==").unwrap_err(),
+            ConversionError::InvalidPadding
+        );
+    }
+
+    #[test]
+    fn should_reject_trailing_bits() {
+        // "YR==" would be 'a' with a stray bit left over
+        assert_eq!(decode("YR==").unwrap_err(), ConversionError::TrailingBits);
+        assert_eq!(decode("YWJ=").unwrap_err(), ConversionError::TrailingBits);
+        assert_eq!(
+            decode_unpadded("YWJ").unwrap_err(),
+            ConversionError::TrailingBits
+        );
+        assert!(externalbase64::decode("YR==").is_err());
+    }
+
+    #[test]
+    fn decode_without_padding() {
+        assert_eq!(decode_unpadded("YQ").unwrap(), b"a");
+        assert_eq!(decode_unpadded("YWI").unwrap(), b"ab");
+        assert_eq!(decode_unpadded("YWJj").unwrap(), b"abc");
+    }
+
+    #[test]
+    fn unpadded_should_reject_stray_chars() {
+        assert_eq!(
+            decode_unpadded("YWJjZ").unwrap_err(),
+            ConversionError::InvalidLength
+        );
+    }
+
+    #[test]
+    fn decode_url_safe_alphabet() {
+        let expected = vec![0xFB, 0xFF, 0xBF, 0xFE];
+        assert_eq!(decode_url_safe("-_-__g==").unwrap(), expected);
+        assert_eq!(decode_url_safe_unpadded("-_-__g").unwrap(), expected);
+    }
+
     #[test]
     fn should_handle_zeroes_midstring() {
         let encoded = String::from("0A0=");
@@ -135,6 +208,84 @@ mod tests {
         assert_eq!(bytes, externalbase64::decode(encoded).unwrap());
     }
 
+    #[test]
+    fn should_keep_all_zero_output() {
+        assert_eq!(decode("AA==").unwrap(), vec![0]);
+        assert_eq!(decode("AAA=").unwrap(), vec![0, 0]);
+        assert_eq!(decode("AAAA").unwrap(), vec![0, 0, 0]);
+        assert_eq!(decode_unpadded("AAA").unwrap(), vec![0, 0]);
+    }
+
+    #[test]
+    fn should_match_rust_base64_on_zeroes() {
+        for length in 0..=32 {
+            let zeroes = vec![0_u8; length];
+            let encoded = externalbase64::encode(&zeroes);
+            assert_eq!(decode(&encoded).unwrap(), zeroes);
+            assert_eq!(
+                decode(&encoded).unwrap(),
+                externalbase64::decode(&encoded).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn should_match_rust_base64_on_zero_suffixes() {
+        let prefixes: [&[u8]; 4] = [b"", b"\xd0", b"\xd0\x0d", b"YELLOW SUBMARINE"];
+        for prefix in prefixes {
+            for zeroes in 1..=7 {
+                let mut data = prefix.to_vec();
+                data.resize(prefix.len() + zeroes, 0);
+
+                let encoded = externalbase64::encode(&data);
+                assert_eq!(decode(&encoded).unwrap(), data);
+                assert_eq!(
+                    decode(&encoded).unwrap(),
+                    externalbase64::decode(&encoded).unwrap()
+                );
+
+                let unpadded =
+                    externalbase64::encode_config(&data, externalbase64::STANDARD_NO_PAD);
+                assert_eq!(decode_unpadded(&unpadded).unwrap(), data);
+            }
+        }
+    }
+
+    #[test]
+    fn should_reject_a_quartet_of_mostly_padding() {
+        assert_eq!(decode("Y===").unwrap_err(), ConversionError::InvalidPadding);
+    }
+
+    #[test]
+    fn decode_lenient_skips_line_breaks() {
+        assert_eq!(decode_lenient("YW\r\nJj\n").unwrap(), b"abc");
+        assert_eq!(decode_lenient(" YQ=\t= ").unwrap(), b"a");
+        assert!(decode("YW\nJj").is_err());
+    }
+
+    #[test]
+    fn decode_lenient_reports_original_position() {
+        assert_eq!(
+            decode_lenient("YWJj\nYW!j").unwrap_err(),
+            ConversionError::InvalidCharacter { index: 7, ch: '!' }
+        );
+        assert_eq!(
+            decode_lenient("YWJj\nYW").unwrap_err(),
+            ConversionError::InvalidLength
+        );
+    }
+
+    #[test]
+    fn decode_lenient_handles_wrapped_file() {
+        let input = include_str!("../../../../files/pset1challenge6.txt");
+        let cleaned_input: String = input.chars().filter(|c| !c.is_ascii_whitespace()).collect();
+
+        assert_eq!(
+            decode_lenient(input).unwrap(),
+            decode(&cleaned_input).unwrap()
+        );
+    }
+
     #[test]
     fn should_match_decode_from_rust_base64() {
         let input = include_str!("../../../../files/pset1challenge6.txt");
@@ -1,64 +1,113 @@
 use std::iter::FromIterator;
 
-use super::model::ClassicBase64;
+use super::fast;
+use super::model::{ClassicBase64, CLASSIC_BASE64_TABLE, URL_SAFE_BASE64_TABLE};
 use crate::lib::conversion::alphabet::Alphabet;
+use crate::lib::conversion::radix::BASE64;
+use crate::lib::conversion::table::SymbolTable;
+use crate::lib::conversion::ConversionError;
 
 pub fn encode(data: &[u8]) -> String {
+    encode_built_in(&CLASSIC_BASE64_TABLE, data, true)
+}
+
+/// Encode without trailing `=` padding.
+pub fn encode_unpadded(data: &[u8]) -> String {
+    encode_built_in(&CLASSIC_BASE64_TABLE, data, false)
+}
+
+/// Encode with the URL and filename safe alphabet (RFC 4648 §5).
+pub fn encode_url_safe(data: &[u8]) -> String {
+    encode_built_in(&URL_SAFE_BASE64_TABLE, data, true)
+}
+
+/// Encode with the URL-safe alphabet and no padding, as JWTs do.
+pub fn encode_url_safe_unpadded(data: &[u8]) -> String {
+    encode_built_in(&URL_SAFE_BASE64_TABLE, data, false)
+}
+
+/// Characters `encode` produces for `bytes` bytes
+pub fn encoded_len(bytes: usize) -> usize {
+    BASE64.encoded_len(bytes, true)
+}
+
+/// Characters `encode_unpadded` produces for `bytes` bytes
+pub fn encoded_len_unpadded(bytes: usize) -> usize {
+    BASE64.encoded_len(bytes, false)
+}
+
+/// Encode into the front of `out` without allocating, returning the bytes written.
+pub fn encode_to_slice(data: &[u8], out: &mut [u8]) -> Result<usize, ConversionError> {
     let classic_alphabet = &ClassicBase64 {};
-    encode_using_alphabet(classic_alphabet, data)
-}
-
-pub fn encode_using_alphabet<T: Alphabet>(alphabet: &T, data: &[u8]) -> String {
-    let encoded = data
-        .chunks(3)
-        .map(split)
-        .flat_map(|chunk| encode_chunk(alphabet, &chunk));
-
-    String::from_iter(encoded)
-}
-
-/// Turn u8 chunks into divided six-byte chunks
-fn split(chunk: &[u8]) -> Vec<u8> {
-    match chunk.len() {
-        1 => vec![
-            // Grab first six,
-            &chunk[0] >> 2,
-            // then last two + 4 zeroes
-            (&chunk[0] & 0b00000011) << 4,
-        ],
-
-        2 => vec![
-            // Grab first six,
-            &chunk[0] >> 2,
-            // Last two + first four of next chunk,
-            (&chunk[0] & 0b00000011) << 4 | &chunk[1] >> 4,
-            // Last four + two zeroes
-            (&chunk[1] & 0b00001111) << 2,
-        ],
-
-        3 => vec![
-            // Grab first six,
-            &chunk[0] >> 2,
-            // Last two + first four of next chunk,
-            (&chunk[0] & 0b00000011) << 4 | &chunk[1] >> 4,
-            // last four of second chunk + two leftmost bits of next byte
-            (&chunk[1] & 0b00001111) << 2 | &chunk[2] >> 6,
-            // use last six bits
-            &chunk[2] & 0b00111111,
-        ],
-
-        _ => unreachable!(),
-    }
+    encode_to_slice_using_alphabet(classic_alphabet, data, out)
+}
+
+/// Line width used by PEM armor (RFC 7468).
+pub const PEM_LINE_WIDTH: usize = 64;
+/// Maximum line width for MIME bodies (RFC 2045).
+pub const MIME_LINE_WIDTH: usize = 76;
+
+/// Encode and break the output into `\n`-separated lines of at most
+/// `line_width` characters. A width of zero leaves the output on one line.
+pub fn encode_wrapped(data: &[u8], line_width: usize) -> String {
+    wrap(encode(data), line_width, "\n")
 }
 
-fn encode_chunk<T: Alphabet>(alphabet: &T, chunk: &[u8]) -> Vec<char> {
-    let mut out = vec![alphabet.get_padding_char(); 4];
-    for i in 0..chunk.len() {
-        if let Some(c) = alphabet.get_char_for_index(chunk[i]) {
-            out[i] = c
-        }
+/// Encode as a MIME body: 76-character lines separated by CRLF.
+pub fn encode_mime(data: &[u8]) -> String {
+    wrap(encode(data), MIME_LINE_WIDTH, "\r\n")
+}
+
+pub fn encode_wrapped_using_alphabet<T: Alphabet>(
+    alphabet: &T,
+    data: &[u8],
+    line_width: usize,
+    line_ending: &str,
+) -> Result<String, ConversionError> {
+    let encoded = encode_using_alphabet(alphabet, data)?;
+    Ok(wrap(encoded, line_width, line_ending))
+}
+
+fn wrap(encoded: String, line_width: usize, line_ending: &str) -> String {
+    if line_width == 0 {
+        return encoded;
     }
-    out
+
+    encoded
+        .chars()
+        .collect::<Vec<char>>()
+        .chunks(line_width)
+        .map(String::from_iter)
+        .collect::<Vec<String>>()
+        .join(line_ending)
+}
+
+/// Fails if `alphabet` isn't 64 symbols, or has no padding character.
+pub fn encode_using_alphabet<T: Alphabet>(
+    alphabet: &T,
+    data: &[u8],
+) -> Result<String, ConversionError> {
+    BASE64.encode(alphabet, data, true)
+}
+
+/// Fails if `alphabet` isn't 64 symbols.
+pub fn encode_unpadded_using_alphabet<T: Alphabet>(
+    alphabet: &T,
+    data: &[u8],
+) -> Result<String, ConversionError> {
+    BASE64.encode(alphabet, data, false)
+}
+
+pub fn encode_to_slice_using_alphabet<T: Alphabet>(
+    alphabet: &T,
+    data: &[u8],
+    out: &mut [u8],
+) -> Result<usize, ConversionError> {
+    BASE64.encode_to_slice(alphabet, data, true, out)
+}
+
+fn encode_built_in(table: &SymbolTable, data: &[u8], padded: bool) -> String {
+    fast::encode(table, data, padded).expect("The RFC 4648 tables have 64 symbols and pad with `=`")
 }
 
 #[cfg(test)]
@@ -114,4 +163,41 @@ mod tests {
 
         assert_eq!(encode(input_data), expected);
     }
+
+    #[test]
+    fn should_encode_without_padding() {
+        assert_eq!(encode_unpadded(b"a"), "YQ");
+        assert_eq!(encode_unpadded(b"ab"), "YWI");
+        assert_eq!(encode_unpadded(b"abc"), "YWJj");
+    }
+
+    #[test]
+    fn should_wrap_lines() {
+        let input_data = "And here be a bit longer text. Let's see how it goes!".as_bytes();
+        let wrapped = encode_wrapped(input_data, 16);
+        assert_eq!(
+            wrapped,
+            "QW5kIGhlcmUgYmUg\nYSBiaXQgbG9uZ2Vy\nIHRleHQuIExldCdz\nIHNlZSBob3cgaXQg\nZ29lcyE="
+        );
+        assert_eq!(encode_wrapped(input_data, 0), encode(input_data));
+        assert_eq!(encode_wrapped(b"abc", PEM_LINE_WIDTH), "YWJj");
+    }
+
+    #[test]
+    fn should_wrap_mime_with_crlf() {
+        let input_data = [0xAB_u8; 120];
+        let encoded = encode_mime(&input_data);
+        let lines: Vec<&str> = encoded.split("\r\n").collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines.iter().all(|line| line.len() <= MIME_LINE_WIDTH));
+        assert_eq!(lines.concat(), encode(&input_data));
+    }
+
+    #[test]
+    fn should_encode_url_safe() {
+        let input_data = [0xFB, 0xFF, 0xBF, 0xFE];
+        assert_eq!(encode(&input_data), "+/+//g==");
+        assert_eq!(encode_url_safe(&input_data), "-_-__g==");
+        assert_eq!(encode_url_safe_unpadded(&input_data), "-_-__g");
+    }
 }
@@ -0,0 +1,179 @@
+/*
+
+Table-driven base64 for the built-in alphabets. Whole groups go through
+the lookup tables eight symbols (six bytes, one 64-bit word) at a time;
+the final group is left to the radix engine so padding rules stay in one
+place. Bad input of any kind falls back to the engine too, which then
+reports exactly the error `decode_using_alphabet` would.
+
+*/
+use crate::lib::conversion::radix::BASE64;
+use crate::lib::conversion::table::SymbolTable;
+use crate::lib::conversion::ConversionError;
+
+/// Fails like `encode_using_alphabet` would if `table` is short of symbols,
+/// or has no padding and `padded` asks for it.
+pub fn encode(table: &SymbolTable, data: &[u8], padded: bool) -> Result<String, ConversionError> {
+    let mut out = Vec::with_capacity(BASE64.encoded_len(data.len(), padded));
+
+    let mut words = data.chunks_exact(6);
+    for chunk in &mut words {
+        let word = u64::from_be_bytes([
+            0, 0, chunk[0], chunk[1], chunk[2], chunk[3], chunk[4], chunk[5],
+        ]);
+        out.extend_from_slice(&[
+            table.symbol((word >> 42) as u8),
+            table.symbol((word >> 36) as u8),
+            table.symbol((word >> 30) as u8),
+            table.symbol((word >> 24) as u8),
+            table.symbol((word >> 18) as u8),
+            table.symbol((word >> 12) as u8),
+            table.symbol((word >> 6) as u8),
+            table.symbol(word as u8),
+        ]);
+    }
+    // the engine checks the table on the way, however short the remainder
+    BASE64.encode_with(table, words.remainder(), padded, |c| out.push(c as u8))?;
+
+    Ok(String::from_utf8(out).expect("Symbol tables only hold ASCII"))
+}
+
+pub fn decode(table: &SymbolTable, data: &str, padded: bool) -> Result<Vec<u8>, ConversionError> {
+    match decode_fast(table, data.as_bytes(), padded) {
+        Some(decoded) => Ok(decoded),
+        None => BASE64.decode(table, data, padded),
+    }
+}
+
+fn decode_fast(table: &SymbolTable, bytes: &[u8], padded: bool) -> Option<Vec<u8>> {
+    if padded && !bytes.len().is_multiple_of(4) {
+        return None;
+    }
+
+    // every group but the last one, which may be short or padded
+    let body = bytes.len().saturating_sub(1) / 4 * 4;
+    let mut out = Vec::with_capacity(bytes.len() / 4 * 3 + 3);
+
+    let mut words = bytes[..body].chunks_exact(8);
+    for chunk in &mut words {
+        let indices = [
+            table.index(chunk[0]),
+            table.index(chunk[1]),
+            table.index(chunk[2]),
+            table.index(chunk[3]),
+            table.index(chunk[4]),
+            table.index(chunk[5]),
+            table.index(chunk[6]),
+            table.index(chunk[7]),
+        ];
+        if indices.iter().fold(0, |seen, &index| seen | index) & 0x80 != 0 {
+            return None;
+        }
+        let word = indices
+            .iter()
+            .fold(0_u64, |word, &index| word << 6 | index as u64);
+        out.extend_from_slice(&word.to_be_bytes()[2..]);
+    }
+
+    for chunk in words.remainder().chunks_exact(4) {
+        let indices = [
+            table.index(chunk[0]),
+            table.index(chunk[1]),
+            table.index(chunk[2]),
+            table.index(chunk[3]),
+        ];
+        if indices.iter().fold(0, |seen, &index| seen | index) & 0x80 != 0 {
+            return None;
+        }
+        let group = indices
+            .iter()
+            .fold(0_u32, |group, &index| group << 6 | index as u32);
+        out.extend_from_slice(&group.to_be_bytes()[1..]);
+    }
+
+    let tail = &bytes[body..];
+    if !tail.is_ascii() {
+        return None;
+    }
+    BASE64
+        .decode_with(
+            table,
+            tail.iter().map(|&byte| byte as char),
+            tail.len(),
+            padded,
+            |byte| out.push(byte),
+        )
+        .ok()?;
+
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lib::conversion::base64::model::CLASSIC_BASE64_TABLE;
+    use crate::lib::conversion::base64::ClassicBase64;
+
+    #[test]
+    fn should_match_the_radix_engine() {
+        let data: Vec<u8> = (0..=255_u8).rev().collect();
+        for length in 0..=64 {
+            for padded in [false, true] {
+                let slice = &data[..length];
+                let expected = BASE64.encode(&ClassicBase64 {}, slice, padded).unwrap();
+                assert_eq!(
+                    encode(&CLASSIC_BASE64_TABLE, slice, padded).unwrap(),
+                    expected
+                );
+                assert_eq!(
+                    decode(&CLASSIC_BASE64_TABLE, &expected, padded).unwrap(),
+                    slice
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn should_not_drop_padding_it_cannot_write() {
+        let unpadded = SymbolTable::new(
+            b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/",
+            None,
+        );
+        assert_eq!(encode(&unpadded, b"hi", false).unwrap(), "aGk");
+        assert_eq!(
+            encode(&unpadded, b"hi", true),
+            Err(ConversionError::NoPaddingCharacter)
+        );
+        assert_eq!(
+            encode(
+                &SymbolTable::new(b"0123456789ABCDEF", None),
+                b"abcdef",
+                false
+            ),
+            Err(ConversionError::WrongAlphabetSize { needed: 64 })
+        );
+    }
+
+    #[test]
+    fn should_report_the_same_errors() {
+        let inputs = [
+            "YWJjZGVmZ2hp!mts",
+            "YWJjZGVm\u{141}2hpamts",
+            "YWJjZGVmZ2hpamt=",
+            "YQ==YWJjZGVmZ2hp",
+            "YWJjZGVmZ2hpamtsYR==",
+            "YWJjZGVmZ2hpamtsY",
+            "YWJj====ZGVm",
+        ];
+        for input in inputs {
+            for padded in [false, true] {
+                assert_eq!(
+                    decode(&CLASSIC_BASE64_TABLE, input, padded),
+                    BASE64.decode(&ClassicBase64 {}, input, padded),
+                    "{}",
+                    input
+                );
+            }
+        }
+    }
+}
@@ -6,12 +6,30 @@ Thank you to:
 https://tiemenwaterreus.com/posts/implementing-base64-in-rust/
 
 */
+mod constant_time;
 mod decode;
 mod encode;
+mod fast;
 mod model;
+mod stream;
 
-pub use decode::decode;
-pub use encode::encode;
+pub use constant_time::{
+    decode_constant_time, decode_url_safe_constant_time, encode_constant_time,
+    encode_url_safe_constant_time,
+};
+pub use decode::{
+    decode, decode_lenient, decode_lenient_using_alphabet, decode_to_slice,
+    decode_to_slice_using_alphabet, decode_unpadded, decode_unpadded_using_alphabet,
+    decode_url_safe, decode_url_safe_unpadded, decode_using_alphabet, decoded_len,
+};
+pub use encode::{
+    encode, encode_mime, encode_to_slice, encode_to_slice_using_alphabet, encode_unpadded,
+    encode_unpadded_using_alphabet, encode_url_safe, encode_url_safe_unpadded,
+    encode_using_alphabet, encode_wrapped, encode_wrapped_using_alphabet, encoded_len,
+    encoded_len_unpadded, MIME_LINE_WIDTH, PEM_LINE_WIDTH,
+};
+pub use model::{ClassicBase64, UrlSafeBase64};
+pub use stream::{Base64Decoder, Base64Encoder};
 
 #[cfg(test)]
 mod tests {
@@ -33,4 +51,71 @@ mod tests {
         assert_eq!(encoded, "3q2+7w==");
         assert_eq!(decode(dbg!(encoded).as_str()).unwrap(), data);
     }
+
+    #[test]
+    fn should_round_trip_wrapped() {
+        let data: Vec<u8> = (0..=255_u8).cycle().take(1000).collect();
+        assert_eq!(
+            decode_lenient(&encode_wrapped(&data, PEM_LINE_WIDTH)).unwrap(),
+            data
+        );
+        assert_eq!(decode_lenient(&encode_mime(&data)).unwrap(), data);
+    }
+
+    #[test]
+    fn should_round_trip_through_slices() {
+        let data = sample_data();
+        let mut encoded = [0_u8; 344];
+        let mut decoded = [0_u8; 256];
+        for length in 0..=data.len() {
+            let slice = &data[..length];
+            let written = encode_to_slice(slice, &mut encoded).unwrap();
+            assert_eq!(written, encoded_len(length));
+            let text = std::str::from_utf8(&encoded[..written]).unwrap();
+            assert_eq!(text, encode(slice));
+
+            assert_eq!(decoded_len(text), Ok(length));
+            assert_eq!(decode_to_slice(text, &mut decoded), Ok(length));
+            assert_eq!(&decoded[..length], slice);
+        }
+        assert_eq!(encoded_len_unpadded(4), encode_unpadded(b"abcd").len());
+    }
+
+    fn sample_data() -> Vec<u8> {
+        // ends in a zero byte, with plenty of bytes landing on indices 62/63
+        (0..=255_u8).rev().collect()
+    }
+
+    #[test]
+    fn should_match_rust_base64_url_safe() {
+        let data = sample_data();
+        for length in 0..=data.len() {
+            let slice = &data[..length];
+            let theirs = externalbase64::encode_config(slice, externalbase64::URL_SAFE);
+            assert_eq!(encode_url_safe(slice), theirs);
+            assert_eq!(decode_url_safe(&theirs).unwrap(), slice);
+        }
+    }
+
+    #[test]
+    fn should_match_rust_base64_url_safe_no_pad() {
+        let data = sample_data();
+        for length in 0..=data.len() {
+            let slice = &data[..length];
+            let theirs = externalbase64::encode_config(slice, externalbase64::URL_SAFE_NO_PAD);
+            assert_eq!(encode_url_safe_unpadded(slice), theirs);
+            assert_eq!(decode_url_safe_unpadded(&theirs).unwrap(), slice);
+        }
+    }
+
+    #[test]
+    fn should_match_rust_base64_standard_no_pad() {
+        let data = sample_data();
+        for length in 0..=data.len() {
+            let slice = &data[..length];
+            let theirs = externalbase64::encode_config(slice, externalbase64::STANDARD_NO_PAD);
+            assert_eq!(encode_unpadded(slice), theirs);
+            assert_eq!(decode_unpadded(&theirs).unwrap(), slice);
+        }
+    }
 }
@@ -1,45 +1,77 @@
 use crate::lib::conversion::alphabet::Alphabet;
+use crate::lib::conversion::table::SymbolTable;
+
+pub(super) static CLASSIC_BASE64_TABLE: SymbolTable = SymbolTable::new(
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/",
+    Some(b'='),
+);
+pub(super) static URL_SAFE_BASE64_TABLE: SymbolTable = SymbolTable::new(
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_",
+    Some(b'='),
+);
 
 pub struct ClassicBase64;
-const UPPERCASEOFFSET: i8 = 65;
-const LOWERCASEOFFSET: i8 = 71;
-const DIGITOFFSET: i8 = -4;
 
 impl Alphabet for ClassicBase64 {
-    /// Map a char to the index in the base64 alphabet
+    /// Map an index in the base64 alphabet to a char
     fn get_char_for_index(&self, index: u8) -> Option<char> {
-        let index = index as i8;
+        CLASSIC_BASE64_TABLE.get_char_for_index(index)
+    }
+
+    /// Map a char to the index in the base64 alphabet
+    fn get_index_for_char(&self, character: char) -> Option<u8> {
+        CLASSIC_BASE64_TABLE.get_index_for_char(character)
+    }
 
-        let ascii_index = match index {
-            0..=25 => index + UPPERCASEOFFSET,  // A-Z
-            26..=51 => index + LOWERCASEOFFSET, // a-z
-            52..=61 => index + DIGITOFFSET,     // 0-9
-            62 => 43,                           // +
-            63 => 47,                           // /
+    fn get_padding_char(&self) -> Option<char> {
+        Some('=')
+    }
+}
 
-            _ => return None,
-        } as u8;
+/// RFC 4648 §5 alphabet: identical to the classic one except `-` and `_`
+/// take the place of `+` and `/`, so it's safe inside URLs and filenames.
+pub struct UrlSafeBase64;
 
-        Some(ascii_index as char)
+impl Alphabet for UrlSafeBase64 {
+    fn get_char_for_index(&self, index: u8) -> Option<char> {
+        URL_SAFE_BASE64_TABLE.get_char_for_index(index)
     }
 
-    /// Map an index in the Base64 alphabet to a char
     fn get_index_for_char(&self, character: char) -> Option<u8> {
-        let character = character as i8;
-        let base64_index = match character {
-            65..=90 => character - UPPERCASEOFFSET,  // A-Z
-            97..=122 => character - LOWERCASEOFFSET, // a-z
-            48..=57 => character - DIGITOFFSET,      // 0-9
-            43 => 62,                                // +
-            47 => 63,                                // /
+        URL_SAFE_BASE64_TABLE.get_index_for_char(character)
+    }
+
+    fn get_padding_char(&self) -> Option<char> {
+        Some('=')
+    }
+}
 
-            _ => return None,
-        } as u8;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn url_safe_should_swap_last_two_symbols() {
+        let alphabet = UrlSafeBase64 {};
+        assert_eq!(alphabet.get_char_for_index(0), Some('A'));
+        assert_eq!(alphabet.get_char_for_index(62), Some('-'));
+        assert_eq!(alphabet.get_char_for_index(63), Some('_'));
+        assert_eq!(alphabet.get_index_for_char('-'), Some(62));
+        assert_eq!(alphabet.get_index_for_char('_'), Some(63));
+    }
 
-        Some(base64_index)
+    #[test]
+    fn url_safe_should_reject_classic_symbols() {
+        let alphabet = UrlSafeBase64 {};
+        assert_eq!(alphabet.get_index_for_char('+'), None);
+        assert_eq!(alphabet.get_index_for_char('/'), None);
     }
 
-    fn get_padding_char(&self) -> char {
-        '='
+    #[test]
+    fn should_reject_wide_characters() {
+        // U+0141 and U+0161 used to be cut down to 'A' and 'a'
+        let alphabet = ClassicBase64 {};
+        assert_eq!(alphabet.get_index_for_char('\u{141}'), None);
+        assert_eq!(alphabet.get_index_for_char('\u{161}'), None);
     }
 }
@@ -0,0 +1,112 @@
+use std::io::{self, Read, Write};
+
+use super::model::ClassicBase64;
+use crate::lib::conversion::alphabet::Alphabet;
+use crate::lib::conversion::radix::BASE64;
+use crate::lib::conversion::stream::{RadixDecoder, RadixEncoder};
+
+/// Base64-encodes everything written to it into `W`, padding the end on `finish`.
+pub struct Base64Encoder<W: Write, T: Alphabet = ClassicBase64> {
+    inner: RadixEncoder<W, T>,
+}
+
+impl<W: Write> Base64Encoder<W> {
+    pub fn new(writer: W) -> Self {
+        Base64Encoder::with_alphabet(writer, ClassicBase64 {})
+    }
+}
+
+impl<W: Write, T: Alphabet> Base64Encoder<W, T> {
+    pub fn with_alphabet(writer: W, alphabet: T) -> Self {
+        Base64Encoder {
+            inner: RadixEncoder::new(writer, alphabet, BASE64, true),
+        }
+    }
+
+    pub fn finish(self) -> io::Result<W> {
+        self.inner.finish()
+    }
+}
+
+impl<W: Write, T: Alphabet> Write for Base64Encoder<W, T> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Reads padded base64 text from `R`, line breaks and all, and yields the decoded bytes.
+pub struct Base64Decoder<R: Read, T: Alphabet = ClassicBase64> {
+    inner: RadixDecoder<R, T>,
+}
+
+impl<R: Read> Base64Decoder<R> {
+    pub fn new(reader: R) -> Self {
+        Base64Decoder::with_alphabet(reader, ClassicBase64 {})
+    }
+}
+
+impl<R: Read, T: Alphabet> Base64Decoder<R, T> {
+    pub fn with_alphabet(reader: R, alphabet: T) -> Self {
+        Base64Decoder {
+            inner: RadixDecoder::new(reader, alphabet, BASE64, true),
+        }
+    }
+}
+
+impl<R: Read, T: Alphabet> Read for Base64Decoder<R, T> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lib::conversion::base64::{decode_lenient, encode, encode_url_safe, UrlSafeBase64};
+
+    #[test]
+    fn should_stream_the_challenge_file() {
+        let input = include_str!("../../../../files/pset1challenge6.txt");
+        let mut decoded = Vec::new();
+        Base64Decoder::new(input.as_bytes())
+            .read_to_end(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, decode_lenient(input).unwrap());
+    }
+
+    #[test]
+    fn should_match_one_shot_encoding() {
+        let data: Vec<u8> = (0..=255_u8).rev().collect();
+        for length in 0..=8 {
+            let mut encoder = Base64Encoder::new(Vec::new());
+            data[..length]
+                .iter()
+                .for_each(|byte| encoder.write_all(&[*byte]).unwrap());
+            let encoded = String::from_utf8(encoder.finish().unwrap()).unwrap();
+            assert_eq!(encoded, encode(&data[..length]));
+        }
+
+        let mut encoder = Base64Encoder::with_alphabet(Vec::new(), UrlSafeBase64 {});
+        encoder.write_all(&data).unwrap();
+        let encoded = String::from_utf8(encoder.finish().unwrap()).unwrap();
+        assert_eq!(encoded, encode_url_safe(&data));
+    }
+
+    #[test]
+    fn should_round_trip() {
+        let data: Vec<u8> = (0..=255_u8).cycle().take(100_000).collect();
+        let mut encoder = Base64Encoder::new(Vec::new());
+        io::copy(&mut data.as_slice(), &mut encoder).unwrap();
+        let encoded = encoder.finish().unwrap();
+
+        let mut decoded = Vec::new();
+        Base64Decoder::new(encoded.as_slice())
+            .read_to_end(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, data);
+    }
+}
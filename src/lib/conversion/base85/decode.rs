@@ -0,0 +1,140 @@
+use super::model::{Ascii85, Z85Alphabet, BASE};
+use crate::lib::conversion::alphabet::{check_symbol_count, Alphabet};
+use crate::lib::conversion::ConversionError;
+
+/// Adobe Ascii85. The `<~ ~>` delimiters are optional and whitespace is skipped.
+pub fn decode(data: &str) -> Result<Vec<u8>, ConversionError> {
+    let ascii85_alphabet = &Ascii85 {};
+    let mut characters: Vec<(usize, char)> = data
+        .chars()
+        .enumerate()
+        .filter(|(_, character)| !character.is_ascii_whitespace())
+        .collect();
+
+    if let [(_, '<'), (_, '~'), ..] = characters[..] {
+        characters.drain(..2);
+    }
+    if let [.., (_, '~'), (_, '>')] = characters[..] {
+        characters.truncate(characters.len() - 2);
+    }
+
+    decode_groups(ascii85_alphabet, &characters, Some('z'))
+}
+
+pub fn decode_z85(data: &str) -> Result<Vec<u8>, ConversionError> {
+    let z85_alphabet = &Z85Alphabet {};
+    decode_using_alphabet(z85_alphabet, data)
+}
+
+/// Plain base 85 groups: no delimiters, no shortcuts, no whitespace.
+/// Fails if `alphabet` isn't exactly 85 symbols, as well as on bad input.
+pub fn decode_using_alphabet<T: Alphabet>(
+    alphabet: &T,
+    data: &str,
+) -> Result<Vec<u8>, ConversionError> {
+    let characters: Vec<(usize, char)> = data.chars().enumerate().collect();
+    decode_groups(alphabet, &characters, None)
+}
+
+fn decode_groups<T: Alphabet>(
+    alphabet: &T,
+    characters: &[(usize, char)],
+    zero_group: Option<char>,
+) -> Result<Vec<u8>, ConversionError> {
+    check_symbol_count(alphabet, BASE as u8)?;
+    let mut out = Vec::with_capacity(characters.len() / 5 * 4);
+    let mut group = [0_u8; 5];
+    let mut filled = 0;
+
+    for &(index, ch) in characters {
+        // the shortcut only counts between groups, never inside one
+        if filled == 0 && Some(ch) == zero_group {
+            out.extend_from_slice(&[0; 4]);
+            continue;
+        }
+
+        group[filled] = alphabet
+            .get_index_for_char(ch)
+            .ok_or(ConversionError::InvalidCharacter { index, ch })?;
+        filled += 1;
+
+        if filled == 5 {
+            out.extend_from_slice(&group_bytes(&group)?);
+            filled = 0;
+        }
+    }
+
+    // a short final group was padded with the highest symbol, n symbols hold n - 1 bytes
+    match filled {
+        0 => {}
+        1 => return Err(ConversionError::InvalidLength),
+        _ => {
+            group[filled..].fill(BASE as u8 - 1);
+            out.extend_from_slice(&group_bytes(&group)?[..filled - 1]);
+        }
+    }
+
+    Ok(out)
+}
+
+fn group_bytes(group: &[u8; 5]) -> Result<[u8; 4], ConversionError> {
+    let value = group
+        .iter()
+        .fold(0_u64, |value, &digit| value * BASE as u64 + digit as u64);
+
+    u32::try_from(value)
+        .map(u32::to_be_bytes)
+        .map_err(|_| ConversionError::Overflow)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_decode_ascii85() {
+        assert_eq!(decode("<~~>").unwrap(), b"");
+        assert_eq!(decode("<~/c~>").unwrap(), b".");
+        assert_eq!(
+            decode("<~9jqo^BlbD-BleB1DJ+*+F(f,q~>").unwrap(),
+            b"Man is distinguished"
+        );
+        assert_eq!(decode("9jqo^\n BlbD-").unwrap(), b"Man is d");
+    }
+
+    #[test]
+    fn should_expand_zero_groups() {
+        assert_eq!(decode("<~z@:E^H!!!!~>").unwrap(), b"\0\0\0\0abc\0\0\0\0");
+        assert_eq!(
+            decode("<~9jzqo^~>").unwrap_err(),
+            ConversionError::InvalidCharacter { index: 4, ch: 'z' }
+        );
+    }
+
+    #[test]
+    fn should_decode_z85() {
+        assert_eq!(
+            decode_z85("HelloWorld").unwrap(),
+            vec![0x86, 0x4F, 0xD2, 0x6F, 0xB5, 0x59, 0xF7, 0x5B]
+        );
+    }
+
+    #[test]
+    fn should_fail_gracefully() {
+        assert_eq!(
+            decode("<~9jqo^B~>").unwrap_err(),
+            ConversionError::InvalidLength
+        );
+        assert_eq!(
+            decode("<~9j~qo^~>").unwrap_err(),
+            ConversionError::InvalidCharacter { index: 4, ch: '~' }
+        );
+        // "s8W-!" is 2^32 - 1, one more no longer fits in four bytes
+        assert_eq!(decode("s8W-!").unwrap(), vec![0xFF; 4]);
+        assert_eq!(decode("s8W-\"").unwrap_err(), ConversionError::Overflow);
+        assert_eq!(
+            decode_z85("Hello World").unwrap_err(),
+            ConversionError::InvalidCharacter { index: 5, ch: ' ' }
+        );
+    }
+}
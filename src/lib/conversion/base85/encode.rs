@@ -0,0 +1,94 @@
+use super::model::{Ascii85, Z85Alphabet, BASE};
+use crate::lib::conversion::alphabet::{check_symbol_count, Alphabet};
+use crate::lib::conversion::ConversionError;
+
+/// Adobe Ascii85, wrapped in `<~ ~>` and with `z` standing in for four zero bytes
+pub fn encode(data: &[u8]) -> String {
+    let ascii85_alphabet = &Ascii85 {};
+    let groups =
+        encode_groups(ascii85_alphabet, data, Some('z')).expect("Ascii85 has all 85 symbols");
+    format!("<~{}~>", groups)
+}
+
+/// ZeroMQ Z85. The spec only covers multiples of four bytes; anything
+/// else gets the same shortened final group Ascii85 uses.
+pub fn encode_z85(data: &[u8]) -> String {
+    let z85_alphabet = &Z85Alphabet {};
+    encode_using_alphabet(z85_alphabet, data).expect("Z85Alphabet has all 85 symbols")
+}
+
+/// Plain base 85 groups: no delimiters, no shortcuts.
+/// Fails if `alphabet` isn't exactly 85 symbols.
+pub fn encode_using_alphabet<T: Alphabet>(
+    alphabet: &T,
+    data: &[u8],
+) -> Result<String, ConversionError> {
+    encode_groups(alphabet, data, None)
+}
+
+/// Four bytes become five symbols; a final group of n bytes keeps n + 1 of them.
+fn encode_groups<T: Alphabet>(
+    alphabet: &T,
+    data: &[u8],
+    zero_group: Option<char>,
+) -> Result<String, ConversionError> {
+    check_symbol_count(alphabet, BASE as u8)?;
+    let mut out = String::with_capacity(data.len().div_ceil(4) * 5);
+
+    for chunk in data.chunks(4) {
+        if let Some(shortcut) = zero_group {
+            if chunk == [0, 0, 0, 0] {
+                out.push(shortcut);
+                continue;
+            }
+        }
+
+        let mut bytes = [0_u8; 4];
+        bytes[..chunk.len()].copy_from_slice(chunk);
+        let mut value = u32::from_be_bytes(bytes);
+
+        let mut digits = [0_u8; 5];
+        for digit in digits.iter_mut().rev() {
+            *digit = (value % BASE) as u8;
+            value /= BASE;
+        }
+
+        for &digit in &digits[..=chunk.len()] {
+            out.push(alphabet.get_char_for_index(digit).ok_or(
+                ConversionError::WrongAlphabetSize {
+                    needed: BASE as usize,
+                },
+            )?);
+        }
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_encode_ascii85() {
+        assert_eq!(encode(b""), "<~~>");
+        assert_eq!(encode(b"."), "<~/c~>");
+        assert_eq!(
+            encode(b"Man is distinguished"),
+            "<~9jqo^BlbD-BleB1DJ+*+F(f,q~>"
+        );
+    }
+
+    #[test]
+    fn should_shorten_whole_zero_groups_only() {
+        assert_eq!(encode(b"\0\0\0\0abc\0\0\0\0"), "<~z@:E^H!!!!~>");
+    }
+
+    #[test]
+    fn should_encode_z85() {
+        // from the ZeroMQ spec (RFC 32)
+        let data = [0x86, 0x4F, 0xD2, 0x6F, 0xB5, 0x59, 0xF7, 0x5B];
+        assert_eq!(encode_z85(&data), "HelloWorld");
+        assert_eq!(encode_z85(&[0; 4]), "00000");
+    }
+}
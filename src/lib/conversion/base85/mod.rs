@@ -0,0 +1,44 @@
+/*
+
+Base85 in its two common dialects: Adobe's Ascii85 (PostScript, PDF) and
+ZeroMQ's Z85. Both read four bytes as a big-endian u32 and write it out as
+five base 85 digits; they only differ in the symbols and the extras.
+
+*/
+mod decode;
+mod encode;
+mod model;
+
+pub use decode::{decode, decode_using_alphabet, decode_z85};
+pub use encode::{encode, encode_using_alphabet, encode_z85};
+pub use model::{Ascii85, Z85Alphabet};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lib::conversion::hex::ClassicHex;
+    use crate::lib::conversion::ConversionError;
+
+    #[test]
+    fn should_convert_back_and_forth() {
+        let data: Vec<u8> = (0..=255_u8).chain([0; 9]).collect();
+        for length in 0..=data.len() {
+            let slice = &data[..length];
+            assert_eq!(decode(&encode(slice)).unwrap(), slice);
+            assert_eq!(decode_z85(&encode_z85(slice)).unwrap(), slice);
+        }
+    }
+
+    #[test]
+    fn should_need_exactly_85_symbols() {
+        let sixteen = &ClassicHex {};
+        assert_eq!(
+            encode_using_alphabet(sixteen, b"\xff\xff\xff\xff"),
+            Err(ConversionError::WrongAlphabetSize { needed: 85 })
+        );
+        assert_eq!(
+            decode_using_alphabet(sixteen, "00000"),
+            Err(ConversionError::WrongAlphabetSize { needed: 85 })
+        );
+    }
+}
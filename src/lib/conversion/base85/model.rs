@@ -0,0 +1,55 @@
+use crate::lib::conversion::alphabet::Alphabet;
+
+pub const BASE: u32 = 85;
+
+const ASCII85OFFSET: u8 = 33;
+const Z85: &str =
+    "0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ.-:+=^!/*?&<>()[]{}@%$#";
+
+/// Adobe's Ascii85: the contiguous run of ASCII from `!` to `u`
+pub struct Ascii85;
+
+impl Alphabet for Ascii85 {
+    fn get_char_for_index(&self, index: u8) -> Option<char> {
+        match index {
+            0..=84 => Some((index + ASCII85OFFSET) as char),
+            _ => None,
+        }
+    }
+
+    fn get_index_for_char(&self, character: char) -> Option<u8> {
+        match character {
+            '!'..='u' => Some(character as u8 - ASCII85OFFSET),
+            _ => None,
+        }
+    }
+}
+
+/// ZeroMQ's Z85, chosen to be safe inside source code and XML
+pub struct Z85Alphabet;
+
+impl Alphabet for Z85Alphabet {
+    fn get_char_for_index(&self, index: u8) -> Option<char> {
+        Z85.as_bytes().get(index as usize).map(|&b| b as char)
+    }
+
+    fn get_index_for_char(&self, character: char) -> Option<u8> {
+        Z85.chars().position(|c| c == character).map(|i| i as u8)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_map_both_ways() {
+        for alphabet in [&Ascii85 {} as &dyn Alphabet, &Z85Alphabet {}] {
+            for index in 0..BASE as u8 {
+                let character = alphabet.get_char_for_index(index).unwrap();
+                assert_eq!(alphabet.get_index_for_char(character), Some(index));
+            }
+            assert_eq!(alphabet.get_char_for_index(BASE as u8), None);
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use super::alphabet::Alphabet;
+use super::radix::BINARY;
+use super::ConversionError;
+
+pub struct BinaryDigits;
+
+impl Alphabet for BinaryDigits {
+    fn get_char_for_index(&self, index: u8) -> Option<char> {
+        match index {
+            0 => Some('0'),
+            1 => Some('1'),
+            _ => None,
+        }
+    }
+
+    fn get_index_for_char(&self, character: char) -> Option<u8> {
+        match character {
+            '0' => Some(0),
+            '1' => Some(1),
+            _ => None,
+        }
+    }
+}
+
+/// Eight `0`/`1` digits per byte, most significant bit first
+pub fn encode(data: &[u8]) -> String {
+    BINARY
+        .encode(&BinaryDigits {}, data, false)
+        .expect("BinaryDigits holds both digits")
+}
+
+pub fn decode(data: &str) -> Result<Vec<u8>, ConversionError> {
+    BINARY.decode(&BinaryDigits {}, data, false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_convert_back_and_forth() {
+        let data = vec![0xD0, 0x0D];
+        let encoded = encode(&data);
+        assert_eq!(encoded, "1101000000001101");
+        assert_eq!(decode(&encoded).unwrap(), data);
+    }
+
+    #[test]
+    fn should_fail_gracefully() {
+        assert_eq!(decode("0101").unwrap_err(), ConversionError::InvalidLength);
+        assert_eq!(
+            decode("01012101").unwrap_err(),
+            ConversionError::InvalidCharacter { index: 4, ch: '2' }
+        );
+    }
+}
@@ -0,0 +1,270 @@
+/*
+
+Guess what an unknown blob is encoded with. Every encoding the input
+decodes under is a candidate; candidates are then weighed by how likely
+random data in that encoding is to come out as exactly this string. A
+smaller alphabet explains a string that fits it much better, so
+`deadbeef` is hex long before it is base64, even though it's valid as both.
+Text is mostly lowercase letters, which is why `attackatdawn` stays text
+though base64 would decode it too.
+
+*/
+use std::fmt;
+
+use super::{base32, base64, hex, ConversionError};
+
+/// How the symbols of raw text split up, whitespace aside
+const LOWERCASE_SHARE: f64 = 0.8;
+const UPPERCASE_SHARE: f64 = 0.08;
+const DIGIT_SHARE: f64 = 0.02;
+/// The 32 ASCII punctuation marks, and whatever lies past ASCII
+const OTHER_SHARE: f64 = 0.1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Encoding {
+    Hex,
+    Base32 {
+        padded: bool,
+    },
+    Base64 {
+        url_safe: bool,
+        padded: bool,
+    },
+    /// Not encoded at all, as far as we can tell
+    Text,
+}
+
+const CANDIDATES: [Encoding; 7] = [
+    Encoding::Hex,
+    Encoding::Base32 { padded: true },
+    Encoding::Base32 { padded: false },
+    Encoding::Base64 {
+        url_safe: false,
+        padded: true,
+    },
+    Encoding::Base64 {
+        url_safe: false,
+        padded: false,
+    },
+    Encoding::Base64 {
+        url_safe: true,
+        padded: true,
+    },
+    Encoding::Base64 {
+        url_safe: true,
+        padded: false,
+    },
+];
+
+impl Encoding {
+    /// Decode `text` as this encoding. Line breaks are skipped, and for hex
+    /// any whitespace at all.
+    pub fn decode(&self, text: &str) -> Result<Vec<u8>, ConversionError> {
+        let unwrapped: String = text.chars().filter(|&c| c != '\r' && c != '\n').collect();
+        match *self {
+            Encoding::Hex => {
+                let digits: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+                hex::decode(&digits)
+            }
+            Encoding::Base32 { padded: true } => base32::decode(&unwrapped),
+            Encoding::Base32 { padded: false } => base32::decode_unpadded(&unwrapped),
+            Encoding::Base64 { url_safe, padded } => match (url_safe, padded) {
+                (false, true) => base64::decode(&unwrapped),
+                (false, false) => base64::decode_unpadded(&unwrapped),
+                (true, true) => base64::decode_url_safe(&unwrapped),
+                (true, false) => base64::decode_url_safe_unpadded(&unwrapped),
+            },
+            Encoding::Text => Ok(text.as_bytes().to_vec()),
+        }
+    }
+
+    /// Chance of drawing `symbol`; the encodings draw evenly from their alphabets
+    fn symbol_probability(&self, symbol: char) -> f64 {
+        match self {
+            Encoding::Hex => 1.0 / 16.0,
+            Encoding::Base32 { .. } => 1.0 / 32.0,
+            Encoding::Base64 { .. } => 1.0 / 64.0,
+            Encoding::Text => match symbol {
+                'a'..='z' => LOWERCASE_SHARE / 26.0,
+                'A'..='Z' => UPPERCASE_SHARE / 26.0,
+                '0'..='9' => DIGIT_SHARE / 10.0,
+                _ => OTHER_SHARE / 32.0,
+            },
+        }
+    }
+
+    /// How much we expect this encoding before looking at the input. The
+    /// URL-safe alphabet is rarer, it only wins when `-` or `_` show up.
+    fn prior(&self) -> f64 {
+        match self {
+            Encoding::Base64 { url_safe: true, .. } => 0.5,
+            _ => 1.0,
+        }
+    }
+
+    /// The padded counterpart of an unpadded encoding
+    fn padded_form(&self) -> Option<Encoding> {
+        match *self {
+            Encoding::Base32 { padded: false } => Some(Encoding::Base32 { padded: true }),
+            Encoding::Base64 {
+                url_safe,
+                padded: false,
+            } => Some(Encoding::Base64 {
+                url_safe,
+                padded: true,
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Encoding::Hex => write!(f, "hex"),
+            Encoding::Base32 { padded } => {
+                write!(f, "base32{}", if *padded { "" } else { " (unpadded)" })
+            }
+            Encoding::Base64 { url_safe, padded } => write!(
+                f,
+                "base64{}{}",
+                if *url_safe { " url-safe" } else { "" },
+                if *padded { "" } else { " (unpadded)" }
+            ),
+            Encoding::Text => write!(f, "text"),
+        }
+    }
+}
+
+/// Every plausible reading of `text` with a confidence between 0 and 1, best
+/// first. The confidences add up to 1, and `Text` is always among them.
+pub fn detect(text: &str) -> Vec<(Encoding, f64)> {
+    let symbols: Vec<char> = text
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '=')
+        .collect();
+    if symbols.is_empty() {
+        return vec![(Encoding::Text, 1.0)];
+    }
+
+    let decodes: Vec<Encoding> = CANDIDATES
+        .iter()
+        .copied()
+        .filter(|encoding| encoding.decode(text).is_ok())
+        .collect();
+    // input without padding that's a whole number of groups reads either way,
+    // call it padded since that's what an encoder would have written too
+    let mut candidates: Vec<Encoding> = decodes
+        .iter()
+        .copied()
+        .filter(|encoding| {
+            encoding
+                .padded_form()
+                .is_none_or(|padded| !decodes.contains(&padded))
+        })
+        .collect();
+    candidates.push(Encoding::Text);
+
+    // log-likelihood of drawing exactly these symbols, softmax'd into confidences
+    let scores: Vec<f64> = candidates
+        .iter()
+        .map(|encoding| {
+            let drawn: f64 = symbols
+                .iter()
+                .map(|&symbol| encoding.symbol_probability(symbol).ln())
+                .sum();
+            encoding.prior().ln() + drawn
+        })
+        .collect();
+    let best = scores.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let total: f64 = scores.iter().map(|score| (score - best).exp()).sum();
+
+    let mut detected: Vec<(Encoding, f64)> = candidates
+        .into_iter()
+        .zip(scores)
+        .map(|(encoding, score)| (encoding, (score - best).exp() / total))
+        .collect();
+    detected.sort_by(|a, b| b.1.total_cmp(&a.1));
+    detected
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn best(text: &str) -> Encoding {
+        detect(text)[0].0
+    }
+
+    #[test]
+    fn should_spot_hex() {
+        let text = include_str!("../../../files/pset1challenge4.txt");
+        assert!(text.lines().all(|line| best(line) == Encoding::Hex));
+        assert_eq!(best("DE AD BE EF"), Encoding::Hex);
+    }
+
+    #[test]
+    fn should_spot_base64() {
+        let text = include_str!("../../../files/pset1challenge6.txt");
+        let standard = Encoding::Base64 {
+            url_safe: false,
+            padded: true,
+        };
+        assert_eq!(best(text), standard);
+        assert_eq!(best("SGVsbG8sIHdvcmxkIQ=="), standard);
+        assert_eq!(
+            best("SGVsbG8sIHdvcmxkIQ"),
+            Encoding::Base64 {
+                url_safe: false,
+                padded: false
+            }
+        );
+        assert_eq!(
+            best("PDw_Pz4-"),
+            Encoding::Base64 {
+                url_safe: true,
+                padded: true
+            }
+        );
+    }
+
+    #[test]
+    fn should_spot_base32() {
+        assert_eq!(best("MZXW6YTBOI======"), Encoding::Base32 { padded: true });
+        assert_eq!(best("MZXW6YTBOI"), Encoding::Base32 { padded: false });
+    }
+
+    #[test]
+    fn should_fall_back_to_text() {
+        assert_eq!(detect("Hello, world!"), vec![(Encoding::Text, 1.0)]);
+        assert_eq!(detect(""), vec![(Encoding::Text, 1.0)]);
+    }
+
+    #[test]
+    fn should_leave_words_that_happen_to_decode() {
+        assert!(Encoding::Base64 {
+            url_safe: false,
+            padded: true
+        }
+        .decode("attackatdawn")
+        .is_ok());
+        assert_eq!(best("attackatdawn"), Encoding::Text);
+        assert_eq!(best("attack at dawn"), Encoding::Text);
+    }
+
+    #[test]
+    fn should_rank_every_reading() {
+        let detected = detect("deadbeef");
+        let total: f64 = detected.iter().map(|(_, confidence)| confidence).sum();
+        assert!((total - 1.0).abs() < 1e-9);
+        assert_eq!(detected[0].0, Encoding::Hex);
+        assert!(detected[0].1 > 0.99);
+        assert!(detected
+            .iter()
+            .any(|(encoding, _)| matches!(encoding, Encoding::Base64 { .. })));
+        assert_eq!(
+            detected[0].0.decode("deadbeef").unwrap(),
+            vec![0xDE, 0xAD, 0xBE, 0xEF]
+        );
+    }
+}
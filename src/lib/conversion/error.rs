@@ -0,0 +1,78 @@
+use std::fmt;
+
+/// Everything that can go wrong when turning text back into bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConversionError {
+    /// The input has a length the encoding can never produce
+    InvalidLength,
+    /// A character outside the alphabet, `index` counting chars from the start
+    InvalidCharacter { index: usize, ch: char },
+    /// Padding in the wrong place, or the wrong amount of it
+    InvalidPadding,
+    /// The final symbol carries set bits that don't belong to any byte
+    TrailingBits,
+    /// Padded output was asked for, but the alphabet has no padding character
+    NoPaddingCharacter,
+    /// The alphabet doesn't have the number of symbols the encoding calls for
+    WrongAlphabetSize { needed: usize },
+    /// The data decoded fine but its embedded checksum doesn't match
+    InvalidChecksum,
+    /// A group of symbols adds up to more than its bytes can hold
+    Overflow,
+    /// The caller's output buffer can't take the whole result
+    BufferTooSmall { needed: usize, available: usize },
+    /// A framing line the format requires, like uuencode's `begin`/`end`, isn't there
+    MissingBoundary,
+    /// A dump offset that goes backwards, lands mid-line, or skips further than a `*` may fill
+    InvalidOffset { index: usize },
+    /// A symbol that takes more than one byte, where the output is one byte per symbol
+    NonAsciiSymbol { ch: char },
+}
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConversionError::InvalidLength => write!(f, "invalid input length"),
+            ConversionError::InvalidCharacter { index, ch } => {
+                write!(f, "invalid character {:?} at index {}", ch, index)
+            }
+            ConversionError::InvalidPadding => write!(f, "invalid padding"),
+            ConversionError::TrailingBits => write!(f, "trailing bits set in final symbol"),
+            ConversionError::NoPaddingCharacter => write!(f, "alphabet has no padding character"),
+            ConversionError::WrongAlphabetSize { needed } => {
+                write!(f, "alphabet needs exactly {} symbols", needed)
+            }
+            ConversionError::InvalidChecksum => write!(f, "checksum mismatch"),
+            ConversionError::Overflow => write!(f, "group value overflows its bytes"),
+            ConversionError::BufferTooSmall { needed, available } => write!(
+                f,
+                "output needs {} bytes but the buffer holds {}",
+                needed, available
+            ),
+            ConversionError::MissingBoundary => write!(f, "missing begin or end line"),
+            ConversionError::InvalidOffset { index } => {
+                write!(
+                    f,
+                    "offset at index {} doesn't follow from the lines before it",
+                    index
+                )
+            }
+            ConversionError::NonAsciiSymbol { ch } => {
+                write!(f, "symbol {:?} doesn't fit in a single byte", ch)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConversionError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_report_where_it_broke() {
+        let error = ConversionError::InvalidCharacter { index: 3, ch: '!' };
+        assert_eq!(error.to_string(), "invalid character '!' at index 3");
+    }
+}
@@ -0,0 +1,100 @@
+/*
+
+Hex for secret material. Digits are produced and read back with plain
+arithmetic on masks, so no branch or table index ever depends on the data.
+Only the verdict is branched on, once, after the whole input has been seen;
+a bad input is then handed to the regular decoder to describe the problem.
+
+*/
+use super::decode::decode;
+use crate::lib::conversion::ConversionError;
+
+pub fn encode_constant_time(data: &[u8]) -> String {
+    let mut out = String::with_capacity(data.len() * 2);
+    for &byte in data {
+        out.push(encode_nibble(byte >> 4) as char);
+        out.push(encode_nibble(byte & 0x0F) as char);
+    }
+    out
+}
+
+pub fn decode_constant_time(data: &str) -> Result<Vec<u8>, ConversionError> {
+    let bytes = data.as_bytes();
+    if !bytes.len().is_multiple_of(2) {
+        return decode(data);
+    }
+
+    let mut out = Vec::with_capacity(bytes.len() / 2);
+    let mut invalid: i16 = 0;
+    for pair in bytes.chunks_exact(2) {
+        let (high, low) = (decode_nibble(pair[0]), decode_nibble(pair[1]));
+        invalid |= high | low;
+        out.push((high << 4 | low) as u8);
+    }
+
+    // any digit that didn't decode left the sign bit set
+    match invalid < 0 {
+        true => decode(data),
+        false => Ok(out),
+    }
+}
+
+/// `0-9` then `A-F`: add 7 more past the digits, by mask rather than branch
+fn encode_nibble(nibble: u8) -> u8 {
+    let nibble = nibble as i16;
+    (nibble + 0x30 + ((9 - nibble) >> 8 & 7)) as u8
+}
+
+/// The digit's value, or -1. `(lo - 1 - c) & (c - hi - 1)` is negative exactly
+/// when `lo <= c <= hi`, and shifting that down gives an all-ones mask.
+fn decode_nibble(character: u8) -> i16 {
+    let c = character as i16;
+    let mut value: i16 = -1;
+    value += ((0x2F - c) & (c - 0x3A)) >> 8 & (c - 0x2F); // 0-9
+    value += ((0x40 - c) & (c - 0x47)) >> 8 & (c - 0x36); // A-F
+    value += ((0x60 - c) & (c - 0x67)) >> 8 & (c - 0x56); // a-f
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lib::conversion::alphabet::Alphabet;
+    use crate::lib::conversion::hex::{encode, ClassicHex};
+
+    #[test]
+    fn should_agree_with_the_alphabet_on_every_input() {
+        let alphabet = ClassicHex {};
+        for nibble in 0..16 {
+            assert_eq!(
+                Some(encode_nibble(nibble) as char),
+                alphabet.get_char_for_index(nibble)
+            );
+        }
+        for byte in 0..=255_u8 {
+            let expected = alphabet
+                .get_index_for_char(byte as char)
+                .map_or(-1, i16::from);
+            assert_eq!(decode_nibble(byte), expected, "{:#x}", byte);
+        }
+    }
+
+    #[test]
+    fn should_agree_with_the_regular_codec() {
+        let data: Vec<u8> = (0..=255_u8).rev().collect();
+        for length in 0..=data.len() {
+            let slice = &data[..length];
+            let encoded = encode_constant_time(slice);
+            assert_eq!(encoded, encode(slice));
+            assert_eq!(decode_constant_time(&encoded).unwrap(), slice);
+            assert_eq!(
+                decode_constant_time(&encoded.to_lowercase()).unwrap(),
+                slice
+            );
+        }
+
+        for input in ["D00DCAB", "D00DCAXE", "D0\u{141}D", "D00D CA"] {
+            assert_eq!(decode_constant_time(input), decode(input), "{}", input);
+        }
+    }
+}
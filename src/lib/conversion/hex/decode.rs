@@ -1,40 +1,91 @@
-use std::io;
-
-use super::model::ClassicHex;
+use super::fast;
+use super::model::{ClassicHex, CLASSIC_HEX_TABLE};
 use crate::lib::conversion::alphabet::Alphabet;
+use crate::lib::conversion::radix::HEX;
+use crate::lib::conversion::ConversionError;
+
+/// Separators skipped between digits when asked to
+const SEPARATORS: [char; 4] = [':', '-', ',', '_'];
 
-pub fn decode(data: &str) -> Result<Vec<u8>, io::Error> {
-    let classic_alphabet = ClassicHex {};
-    decode_using_alphabet(&classic_alphabet, data)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DecodeOptions {
+    /// Drop `0x` / `0X` in front of a run of digits and `\x` anywhere
+    pub strip_prefixes: bool,
+    /// Skip whitespace as well as `:`, `-`, `,` and `_`
+    pub skip_separators: bool,
 }
 
-fn decode_using_alphabet<T: Alphabet>(alphabet: &T, data: &str) -> Result<Vec<u8>, io::Error> {
-    if data.len() % 2 != 0 {
-        return Err(io::Error::from(io::ErrorKind::InvalidInput));
-    }
+pub fn decode(data: &str) -> Result<Vec<u8>, ConversionError> {
+    fast::decode(&CLASSIC_HEX_TABLE, data)
+}
+
+/// Bytes `decode` will produce for `data`
+pub fn decoded_len(data: &str) -> Result<usize, ConversionError> {
+    HEX.decoded_len(&ClassicHex {}, data)
+}
 
-    data.chars()
-        .collect::<Vec<char>>()
-        .chunks(2)
-        .map::<Result<u8, io::Error>, _>(|chars| {
-            let get_or_err = |&ch| {
-                alphabet
-                    .get_index_for_char(ch)
-                    .ok_or_else(|| io::Error::from(io::ErrorKind::InvalidInput))
-            };
-
-            let tuple = match chars {
-                [first, second] => (get_or_err(first)?, get_or_err(second)?),
-                _ => unreachable!(),
-            };
-
-            Ok(combine_bytes(tuple))
-        })
-        .collect()
+/// Decode into the front of `out` without allocating, returning the bytes written.
+pub fn decode_to_slice(data: &str, out: &mut [u8]) -> Result<usize, ConversionError> {
+    HEX.decode_to_slice(&ClassicHex {}, data, false, out)
 }
 
-fn combine_bytes(bytes: (u8, u8)) -> u8 {
-    ((bytes.0 & 0x0F) << 4) | bytes.1 & 0x0F
+/// Decode whatever a debugger, C source or a MAC address printout hands us:
+/// `0xDE 0xAD`, `\xde\xad`, `de:ad`, `DE-AD` all come out as `[0xDE, 0xAD]`.
+pub fn decode_lenient(data: &str) -> Result<Vec<u8>, ConversionError> {
+    let options = DecodeOptions {
+        strip_prefixes: true,
+        skip_separators: true,
+    };
+    decode_with_options(data, &options)
+}
+
+pub fn decode_with_options(
+    data: &str,
+    options: &DecodeOptions,
+) -> Result<Vec<u8>, ConversionError> {
+    let characters: Vec<char> = data.chars().collect();
+    let is_separator = |c: char| c.is_whitespace() || SEPARATORS.contains(&c);
+
+    // keep every digit along with where it came from
+    let mut digits: Vec<(usize, char)> = Vec::with_capacity(characters.len());
+    let mut i = 0;
+    while i < characters.len() {
+        let next = characters.get(i + 1).copied();
+        let starts_run = i == 0 || is_separator(characters[i - 1]);
+        let is_prefix = match characters[i] {
+            '\\' => next == Some('x'),
+            '0' => starts_run && matches!(next, Some('x') | Some('X')),
+            _ => false,
+        };
+
+        if options.strip_prefixes && is_prefix {
+            i += 2;
+        } else if options.skip_separators && is_separator(characters[i]) {
+            i += 1;
+        } else {
+            digits.push((i, characters[i]));
+            i += 1;
+        }
+    }
+
+    let mut result = Vec::with_capacity(digits.len() / 2);
+    HEX.decode_with(
+        &ClassicHex {},
+        digits.iter().map(|&(_, ch)| ch),
+        digits.len(),
+        false,
+        |byte| result.push(byte),
+    )
+    .map_err(|error| match error {
+        // point back into the original input
+        ConversionError::InvalidCharacter { index, ch } => ConversionError::InvalidCharacter {
+            index: digits[index].0,
+            ch,
+        },
+        _ => error,
+    })?;
+
+    Ok(result)
 }
 
 #[cfg(test)]
@@ -75,8 +126,55 @@ mod tests {
     }
 
     #[test]
-    fn should_combine_bytes() {
-        let bytes = (0x0D, 0x0F);
-        assert_eq!(combine_bytes(bytes), 0xDF)
+    fn should_decode_prefixes_and_separators() {
+        let expected = vec![0xDE, 0xAD, 0xBE, 0xEF];
+        assert_eq!(decode_lenient("0xDEADBEEF").unwrap(), expected);
+        assert_eq!(decode_lenient("0xde, 0xad, 0xbe, 0xef").unwrap(), expected);
+        assert_eq!(decode_lenient("\\xde\\xad\\xbe\\xef").unwrap(), expected);
+        assert_eq!(decode_lenient("de:ad:be:ef").unwrap(), expected);
+        assert_eq!(decode_lenient("DE-AD BE\nEF").unwrap(), expected);
+        assert_eq!(decode_lenient("").unwrap(), vec![]);
+    }
+
+    #[test]
+    fn should_only_skip_what_it_is_told_to() {
+        let separators_only = DecodeOptions {
+            strip_prefixes: false,
+            skip_separators: true,
+        };
+        assert_eq!(
+            decode_with_options("de:ad", &separators_only).unwrap(),
+            vec![0xDE, 0xAD]
+        );
+        assert_eq!(
+            decode_with_options("0xde", &separators_only).unwrap_err(),
+            ConversionError::InvalidCharacter { index: 1, ch: 'x' }
+        );
+        assert!(decode_with_options("de:ad", &DecodeOptions::default()).is_err());
+    }
+
+    #[test]
+    fn lenient_should_report_original_position() {
+        assert_eq!(
+            decode_lenient("0xde, 0xag").unwrap_err(),
+            ConversionError::InvalidCharacter { index: 9, ch: 'g' }
+        );
+        assert_eq!(
+            decode_lenient("de:a").unwrap_err(),
+            ConversionError::InvalidLength
+        );
+    }
+
+    #[test]
+    fn should_report_where_it_broke() {
+        assert_eq!(decode("BADA5").unwrap_err(), ConversionError::InvalidLength);
+        assert_eq!(
+            decode("C0FFEG").unwrap_err(),
+            ConversionError::InvalidCharacter { index: 5, ch: 'G' }
+        );
+        assert_eq!(
+            decode("ÀB").unwrap_err(),
+            ConversionError::InvalidCharacter { index: 0, ch: 'À' }
+        );
     }
 }
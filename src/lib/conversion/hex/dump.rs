@@ -0,0 +1,353 @@
+use super::decode::decode;
+use super::encode::encode_lowercase;
+use crate::lib::conversion::ConversionError;
+
+/// Cipher blocks are 16 bytes, that's what block markers line up with
+const BLOCK_SIZE: usize = 16;
+const BLOCK_MARKER: &str = "|";
+/// Most bytes one `*` line may stand for, so a bogus offset can't ask for terabytes
+const MAX_REPEATED: usize = 1 << 24;
+
+#[derive(Debug, Clone, Copy)]
+pub struct DumpOptions {
+    pub bytes_per_line: usize,
+    /// Bytes printed together without a space, zero for one run per line
+    pub group_size: usize,
+    /// Show the printable ASCII column on the right
+    pub ascii: bool,
+    /// Put a `|` between groups wherever a 16 byte block starts mid-line
+    pub block_markers: bool,
+}
+
+impl Default for DumpOptions {
+    /// Same layout as plain `xxd`
+    fn default() -> Self {
+        DumpOptions {
+            bytes_per_line: 16,
+            group_size: 2,
+            ascii: true,
+            block_markers: false,
+        }
+    }
+}
+
+/// Render `data` the way `xxd` does by default.
+pub fn dump(data: &[u8]) -> String {
+    dump_with_options(data, &DumpOptions::default())
+}
+
+pub fn dump_with_options(data: &[u8], options: &DumpOptions) -> String {
+    let bytes_per_line = options.bytes_per_line.max(1);
+
+    // short last lines still get their text lined up with the rest; block
+    // markers move from line to line, so line it up with the widest full one
+    let full_line = vec![0; bytes_per_line];
+    let full_width = (0..BLOCK_SIZE)
+        .map(|line| hex_column(&full_line, line * bytes_per_line, options).len())
+        .max()
+        .unwrap_or(0);
+
+    data.chunks(bytes_per_line)
+        .enumerate()
+        .map(|(line, bytes)| {
+            let offset = line * bytes_per_line;
+            let hex = hex_column(bytes, offset, options);
+            let mut out = format!("{:08x}: {}", offset, hex);
+
+            if options.ascii {
+                out.push_str(&" ".repeat(full_width - hex.len() + 2));
+                out.extend(bytes.iter().map(|&byte| printable(byte)));
+            }
+
+            out
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn hex_column(bytes: &[u8], offset: usize, options: &DumpOptions) -> String {
+    let mut tokens: Vec<String> = Vec::new();
+    let mut group = String::new();
+    let mut group_length = 0;
+
+    for (i, &byte) in bytes.iter().enumerate() {
+        let starts_block = (offset + i).is_multiple_of(BLOCK_SIZE) && i != 0;
+        if options.block_markers && starts_block {
+            tokens.push(std::mem::take(&mut group));
+            tokens.push(BLOCK_MARKER.to_string());
+            group_length = 0;
+        } else if options.group_size != 0 && group_length == options.group_size {
+            tokens.push(std::mem::take(&mut group));
+            group_length = 0;
+        }
+
+        group.push_str(&encode_lowercase(&[byte]));
+        group_length += 1;
+    }
+    tokens.push(group);
+
+    tokens.join(" ")
+}
+
+fn printable(byte: u8) -> char {
+    match byte {
+        0x20..=0x7E => byte as char,
+        _ => '.',
+    }
+}
+
+/// Read `xxd` or `hexdump -C` output (and anything `dump` produces) back
+/// into bytes. Offsets are only used to expand `*` runs of repeated lines,
+/// counting from the first one so a dump can start partway into a file.
+pub fn parse_dump(text: &str) -> Result<Vec<u8>, ConversionError> {
+    let mut out: Vec<u8> = Vec::new();
+    let mut previous_line: Vec<u8> = Vec::new();
+    let mut repeating = false;
+    let mut line_start = 0;
+    let mut first_offset = None;
+
+    for line in text.split('\n') {
+        let characters: Vec<char> = line.trim_end_matches('\r').chars().collect();
+        let line_offset = line_start;
+        line_start += line.chars().count() + 1;
+
+        let mut position = skip_whitespace(&characters, 0);
+        if position == characters.len() {
+            continue;
+        }
+        if characters[position..] == ['*'] {
+            repeating = true;
+            continue;
+        }
+
+        // the offset column, `xxd` ends it with a colon
+        let offset_end = position
+            + characters[position..]
+                .iter()
+                .take_while(|c| !c.is_whitespace())
+                .count();
+        let is_xxd = characters[offset_end - 1] == ':';
+        let offset_digits = &characters[position..offset_end - is_xxd as usize];
+        let offset = parse_offset(offset_digits, line_offset + position)?;
+        let invalid = ConversionError::InvalidOffset {
+            index: line_offset + position,
+        };
+        let offset = offset
+            .checked_sub(*first_offset.get_or_insert(offset))
+            .ok_or(invalid)?;
+
+        if repeating && !previous_line.is_empty() {
+            let missing = offset.checked_sub(out.len()).ok_or(invalid)?;
+            if missing % previous_line.len() != 0 || missing > MAX_REPEATED {
+                return Err(invalid);
+            }
+            for _ in 0..missing / previous_line.len() {
+                out.extend_from_slice(&previous_line);
+            }
+        }
+        repeating = false;
+
+        // `xxd` puts two spaces before the text column, `hexdump -C` fences it with bars
+        position = skip_whitespace(&characters, offset_end);
+        let hex_end = if is_xxd {
+            (position..characters.len())
+                .find(|&i| characters[i] == ' ' && characters.get(i + 1) == Some(&' '))
+                .unwrap_or(characters.len())
+        } else {
+            (position..characters.len())
+                .find(|&i| characters[i] == '|')
+                .unwrap_or(characters.len())
+        };
+
+        let mut line_bytes = Vec::new();
+        while position < hex_end {
+            let token_length = characters[position..hex_end]
+                .iter()
+                .take_while(|c| !c.is_whitespace())
+                .count();
+            let token: String = characters[position..position + token_length]
+                .iter()
+                .collect();
+
+            if token != BLOCK_MARKER {
+                let bytes = decode(&token).map_err(|error| match error {
+                    ConversionError::InvalidCharacter { index, ch } => {
+                        ConversionError::InvalidCharacter {
+                            index: line_offset + position + index,
+                            ch,
+                        }
+                    }
+                    _ => error,
+                })?;
+                line_bytes.extend(bytes);
+            }
+
+            position = skip_whitespace(&characters[..hex_end], position + token_length);
+        }
+
+        out.extend_from_slice(&line_bytes);
+        previous_line = line_bytes;
+    }
+
+    Ok(out)
+}
+
+fn skip_whitespace(characters: &[char], from: usize) -> usize {
+    from + characters[from..]
+        .iter()
+        .take_while(|c| c.is_whitespace())
+        .count()
+}
+
+fn parse_offset(digits: &[char], index: usize) -> Result<usize, ConversionError> {
+    digits
+        .iter()
+        .enumerate()
+        .try_fold(0_usize, |offset, (i, &ch)| {
+            let digit = ch.to_digit(16).ok_or(ConversionError::InvalidCharacter {
+                index: index + i,
+                ch,
+            })?;
+            offset
+                .checked_mul(16)
+                .and_then(|offset| offset.checked_add(digit as usize))
+                .ok_or(ConversionError::Overflow)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &[u8] = b"Hello, world!\n\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0abc";
+
+    #[test]
+    fn should_dump_like_xxd() {
+        let expected = "\
+00000000: 4865 6c6c 6f2c 2077 6f72 6c64 210a 0000  Hello, world!...
+00000010: 0000 0000 0000 0000 0000 0000 0000 0000  ................
+00000020: 0000 0000 0000 0000 0000 0000 0000 0000  ................
+00000030: 0000 0000 0000 0000 0000 0000 0000 6162  ..............ab
+00000040: 63                                       c";
+        assert_eq!(dump(SAMPLE), expected);
+    }
+
+    #[test]
+    fn should_dump_with_options() {
+        let options = DumpOptions {
+            bytes_per_line: 24,
+            group_size: 4,
+            ascii: false,
+            block_markers: true,
+        };
+        let data: Vec<u8> = (0..40).collect();
+        let expected = "\
+00000000: 00010203 04050607 08090a0b 0c0d0e0f | 10111213 14151617
+00000018: 18191a1b 1c1d1e1f | 20212223 24252627";
+        assert_eq!(dump_with_options(&data, &options), expected);
+    }
+
+    #[test]
+    fn should_parse_xxd_output() {
+        assert_eq!(parse_dump(&dump(SAMPLE)).unwrap(), SAMPLE);
+
+        // the text column looks like hex here, it must not be read as such
+        let ambiguous = "00000000: 6162 6364  abcd\n";
+        assert_eq!(parse_dump(ambiguous).unwrap(), b"abcd");
+    }
+
+    #[test]
+    fn should_parse_hexdump_canonical_output() {
+        let text = "\
+00000000  48 65 6c 6c 6f 2c 20 77  6f 72 6c 64 21 0a 00 00  |Hello, world!...|
+00000010  00 00 00 00 00 00 00 00  00 00 00 00 00 00 00 00  |................|
+*
+00000030  00 00 00 00 00 00 00 00  00 00 00 00 00 00 61 62  |..............ab|
+00000040  63                                                |c|
+00000041
+";
+        assert_eq!(parse_dump(text).unwrap(), SAMPLE);
+    }
+
+    #[test]
+    fn should_round_trip_every_layout() {
+        let data: Vec<u8> = (0..=255_u8).rev().collect();
+        for bytes_per_line in [1, 7, 8, 16, 24, 32] {
+            for group_size in [0, 1, 2, 3, 8] {
+                for block_markers in [false, true] {
+                    let options = DumpOptions {
+                        bytes_per_line,
+                        group_size,
+                        ascii: true,
+                        block_markers,
+                    };
+                    let dumped = dump_with_options(&data, &options);
+                    assert_eq!(parse_dump(&dumped).unwrap(), data, "{:?}", options);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn should_report_where_it_broke() {
+        assert_eq!(
+            parse_dump("00000000: 4865\n00000002: 6x6c  lo").unwrap_err(),
+            ConversionError::InvalidCharacter { index: 26, ch: 'x' }
+        );
+        assert_eq!(
+            parse_dump("0000000g: 4865").unwrap_err(),
+            ConversionError::InvalidCharacter { index: 7, ch: 'g' }
+        );
+        assert_eq!(
+            parse_dump("00000000: 486").unwrap_err(),
+            ConversionError::InvalidLength
+        );
+        assert_eq!(
+            parse_dump("ffffffffffffffffffff: 00\n").unwrap_err(),
+            ConversionError::Overflow
+        );
+    }
+
+    #[test]
+    fn should_only_repeat_what_the_offsets_allow() {
+        // a dump that starts partway into a file counts from its first offset
+        let text = "00000100: 0102\n*\n00000106: 03";
+        assert_eq!(parse_dump(text).unwrap(), b"\x01\x02\x01\x02\x01\x02\x03");
+
+        let backwards = "00000010: 0102\n*\n00000000: 03";
+        assert_eq!(
+            parse_dump(backwards).unwrap_err(),
+            ConversionError::InvalidOffset { index: 17 }
+        );
+        let mid_line = "00000000: 0102\n*\n00000005: 03";
+        assert_eq!(
+            parse_dump(mid_line).unwrap_err(),
+            ConversionError::InvalidOffset { index: 17 }
+        );
+        let endless = "00000000: 00\n*\nffffffffffff: 00";
+        assert_eq!(
+            parse_dump(endless).unwrap_err(),
+            ConversionError::InvalidOffset { index: 15 }
+        );
+    }
+
+    #[test]
+    fn should_line_up_text_past_block_markers() {
+        let options = DumpOptions {
+            bytes_per_line: 7,
+            group_size: 0,
+            ascii: true,
+            block_markers: true,
+        };
+        let dumped = dump_with_options(b"abcdefghijklmnopq", &options);
+        let columns: Vec<usize> = dumped
+            .lines()
+            .map(|line| line.rfind("  ").unwrap())
+            .collect();
+        assert!(
+            columns.iter().all(|&column| column == columns[0]),
+            "{}",
+            dumped
+        );
+    }
+}
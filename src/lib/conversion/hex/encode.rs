@@ -1,26 +1,60 @@
-use super::model::ClassicHex;
+use super::fast;
+use super::model::{ClassicHex, LowercaseHex, CLASSIC_HEX_TABLE, LOWERCASE_HEX_TABLE};
 use crate::lib::conversion::alphabet::Alphabet;
+use crate::lib::conversion::radix::HEX;
+use crate::lib::conversion::ConversionError;
+
+/// Every hex alphabet here has its sixteen digits, so encoding can't fail
+const ALL_DIGITS: &str = "The hex alphabets hold all sixteen digits";
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EncodeOptions<'a> {
+    /// Write `a-f` instead of `A-F`
+    pub lowercase: bool,
+    /// Put between every pair of digits, e.g. `":"` for `DE:AD:BE:EF`
+    pub delimiter: &'a str,
+}
 
 pub fn encode(data: &[u8]) -> String {
-    let classic_alphabet = ClassicHex {};
-    encode_using_alphabet(&classic_alphabet, data)
+    fast::encode(&CLASSIC_HEX_TABLE, data)
+}
+
+pub fn encode_lowercase(data: &[u8]) -> String {
+    fast::encode(&LOWERCASE_HEX_TABLE, data)
+}
+
+pub fn encode_with_options(data: &[u8], options: &EncodeOptions) -> String {
+    if options.lowercase {
+        encode_delimited_using_alphabet(&LowercaseHex {}, data, options.delimiter)
+    } else {
+        encode_delimited_using_alphabet(&ClassicHex {}, data, options.delimiter)
+    }
 }
 
-fn encode_using_alphabet<T: Alphabet>(alphabet: &T, data: &[u8]) -> String {
-    data.iter()
-        .map(|&b| split_byte(b))
-        .flat_map(|byte_tuple| {
-            std::iter::once(alphabet.get_char_for_index(byte_tuple.0).unwrap())
-                .chain(std::iter::once(
-                    alphabet.get_char_for_index(byte_tuple.1).unwrap(),
-                ))
-                .collect::<Vec<char>>()
-        })
-        .collect()
+/// Characters `encode` produces for `bytes` bytes
+pub fn encoded_len(bytes: usize) -> usize {
+    HEX.encoded_len(bytes, false)
 }
 
-fn split_byte(byte: u8) -> (u8, u8) {
-    ((byte & 0xF0) >> 4, (byte & 0x0F))
+/// Encode into the front of `out` without allocating, returning the bytes written.
+pub fn encode_to_slice(data: &[u8], out: &mut [u8]) -> Result<usize, ConversionError> {
+    HEX.encode_to_slice(&ClassicHex {}, data, false, out)
+}
+
+fn encode_delimited_using_alphabet<T: Alphabet>(
+    alphabet: &T,
+    data: &[u8],
+    delimiter: &str,
+) -> String {
+    let mut out = String::with_capacity(data.len() * (2 + delimiter.len()));
+    for (i, byte) in data.iter().enumerate() {
+        if i != 0 {
+            out.push_str(delimiter);
+        }
+        HEX.encode_with(alphabet, &[*byte], false, |c| out.push(c))
+            .expect(ALL_DIGITS);
+    }
+    out
 }
 
 #[cfg(test)]
@@ -40,8 +74,29 @@ mod tests {
     }
 
     #[test]
-    fn should_split_bytes() {
-        let byte: u8 = 0xDF_u8;
-        assert_eq!(split_byte(byte), (0xD, 0xF))
+    fn should_encode_lowercase() {
+        let bytes = vec![0xCA, 0xFE, 0x0B];
+        assert_eq!(encode_lowercase(&bytes), "cafe0b");
+    }
+
+    #[test]
+    fn should_encode_with_options() {
+        let bytes = vec![0xDE, 0xAD, 0xBE, 0xEF];
+        let colons = EncodeOptions {
+            lowercase: true,
+            delimiter: ":",
+        };
+        assert_eq!(encode_with_options(&bytes, &colons), "de:ad:be:ef");
+
+        let escapes = EncodeOptions {
+            lowercase: false,
+            delimiter: " ",
+        };
+        assert_eq!(encode_with_options(&bytes, &escapes), "DE AD BE EF");
+        assert_eq!(
+            encode_with_options(&bytes, &EncodeOptions::default()),
+            encode(&bytes)
+        );
+        assert_eq!(encode_with_options(&[], &colons), "");
     }
 }
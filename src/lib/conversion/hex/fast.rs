@@ -0,0 +1,89 @@
+/*
+
+Table-driven hex. Decoding packs sixteen digits into a 64-bit word at a
+time and checks all their lookups with a single test; anything odd falls
+back to the radix engine, which reports the precise error.
+
+*/
+use crate::lib::conversion::radix::HEX;
+use crate::lib::conversion::table::SymbolTable;
+use crate::lib::conversion::ConversionError;
+
+pub fn encode(table: &SymbolTable, data: &[u8]) -> String {
+    let mut out = Vec::with_capacity(data.len() * 2);
+    for &byte in data {
+        out.extend_from_slice(&[table.symbol(byte >> 4), table.symbol(byte & 0x0F)]);
+    }
+
+    String::from_utf8(out).expect("Symbol tables only hold ASCII")
+}
+
+pub fn decode(table: &SymbolTable, data: &str) -> Result<Vec<u8>, ConversionError> {
+    match decode_fast(table, data.as_bytes()) {
+        Some(decoded) => Ok(decoded),
+        None => HEX.decode(table, data, false),
+    }
+}
+
+fn decode_fast(table: &SymbolTable, bytes: &[u8]) -> Option<Vec<u8>> {
+    if !bytes.len().is_multiple_of(2) {
+        return None;
+    }
+    let mut out = Vec::with_capacity(bytes.len() / 2);
+
+    let mut words = bytes.chunks_exact(16);
+    for chunk in &mut words {
+        let (word, seen) = chunk.iter().fold((0_u64, 0_u8), |(word, seen), &byte| {
+            let index = table.index(byte);
+            (word << 4 | index as u64 & 0x0F, seen | index)
+        });
+        if seen & 0x80 != 0 {
+            return None;
+        }
+        out.extend_from_slice(&word.to_be_bytes());
+    }
+
+    for pair in words.remainder().chunks_exact(2) {
+        let (high, low) = (table.index(pair[0]), table.index(pair[1]));
+        if (high | low) & 0x80 != 0 {
+            return None;
+        }
+        out.push(high << 4 | low);
+    }
+
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lib::conversion::hex::model::CLASSIC_HEX_TABLE;
+    use crate::lib::conversion::hex::ClassicHex;
+
+    #[test]
+    fn should_match_the_radix_engine() {
+        let data: Vec<u8> = (0..=255_u8).rev().collect();
+        for length in 0..=40 {
+            let slice = &data[..length];
+            let expected = HEX.encode(&ClassicHex {}, slice, false).unwrap();
+            assert_eq!(encode(&CLASSIC_HEX_TABLE, slice), expected);
+            assert_eq!(decode(&CLASSIC_HEX_TABLE, &expected).unwrap(), slice);
+            assert_eq!(
+                decode(&CLASSIC_HEX_TABLE, &expected.to_lowercase()).unwrap(),
+                slice
+            );
+        }
+    }
+
+    #[test]
+    fn should_report_the_same_errors() {
+        for input in ["00112233445566778899aabbccddeeXf", "0011\u{141}2", "00112"] {
+            assert_eq!(
+                decode(&CLASSIC_HEX_TABLE, input),
+                HEX.decode(&ClassicHex {}, input, false),
+                "{}",
+                input
+            );
+        }
+    }
+}
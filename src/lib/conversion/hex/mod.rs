@@ -1,13 +1,26 @@
+mod constant_time;
 mod decode;
+mod dump;
 mod encode;
+mod fast;
 mod model;
+mod stream;
 
-pub use decode::decode;
-pub use encode::encode;
+pub use constant_time::{decode_constant_time, encode_constant_time};
+pub use decode::{
+    decode, decode_lenient, decode_to_slice, decode_with_options, decoded_len, DecodeOptions,
+};
+pub use dump::{dump, dump_with_options, parse_dump, DumpOptions};
+pub use encode::{
+    encode, encode_lowercase, encode_to_slice, encode_with_options, encoded_len, EncodeOptions,
+};
+pub use model::{ClassicHex, LowercaseHex};
+pub use stream::{HexDecoder, HexEncoder};
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::lib::conversion::ConversionError;
 
     #[test]
     fn should_convert_back_and_forth() {
@@ -16,4 +29,39 @@ mod tests {
         assert_eq!(encoded, "D00DCABE");
         assert_eq!(decode(encoded.as_str()).unwrap(), data);
     }
+
+    #[test]
+    fn should_convert_back_and_forth_through_slices() {
+        let data = vec![0xD0, 0x0D, 0xCA, 0xBE];
+        let mut encoded = [0_u8; 8];
+        assert_eq!(encoded_len(data.len()), 8);
+        assert_eq!(encode_to_slice(&data, &mut encoded), Ok(8));
+        assert_eq!(&encoded, b"D00DCABE");
+
+        let mut decoded = [0_u8; 4];
+        assert_eq!(decoded_len("D00DCABE"), Ok(4));
+        assert_eq!(decode_to_slice("D00DCABE", &mut decoded), Ok(4));
+        assert_eq!(decoded.to_vec(), data);
+        assert_eq!(
+            decode_to_slice("D00DCABE00", &mut decoded),
+            Err(ConversionError::BufferTooSmall {
+                needed: 5,
+                available: 4
+            })
+        );
+        assert_eq!(decoded_len("D00"), Err(ConversionError::InvalidLength));
+    }
+
+    #[test]
+    fn should_convert_back_and_forth_with_options() {
+        let data: Vec<u8> = (0..=255_u8).collect();
+        for delimiter in ["", " ", ":", ", 0x"] {
+            let options = EncodeOptions {
+                lowercase: true,
+                delimiter,
+            };
+            let encoded = encode_with_options(&data, &options);
+            assert_eq!(decode_lenient(&encoded).unwrap(), data);
+        }
+    }
 }
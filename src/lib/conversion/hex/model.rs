@@ -1,32 +1,34 @@
 use crate::lib::conversion::alphabet::Alphabet;
+use crate::lib::conversion::table::SymbolTable;
 
-const DIGITOFFSET: u8 = 48;
-const UPPERCASEOFFSET: u8 = 65 - 10;
-const LOWERCASEOFFSET: u8 = 97 - 10;
+// either case decodes, whichever one we write
+pub(super) static CLASSIC_HEX_TABLE: SymbolTable =
+    SymbolTable::new(b"0123456789ABCDEF", None).with_aliases(b"abcdef", 10);
+pub(super) static LOWERCASE_HEX_TABLE: SymbolTable =
+    SymbolTable::new(b"0123456789abcdef", None).with_aliases(b"ABCDEF", 10);
 
 pub struct ClassicHex;
 
 impl Alphabet for ClassicHex {
     fn get_char_for_index(&self, index: u8) -> Option<char> {
-        match index {
-            0..=9 => Some((index + DIGITOFFSET) as char),
-            10..=15 => Some((index + UPPERCASEOFFSET) as char),
-            _ => None,
-        }
+        CLASSIC_HEX_TABLE.get_char_for_index(index)
     }
 
     fn get_index_for_char(&self, character: char) -> Option<u8> {
-        let character = character as u8;
-        match character {
-            48..=57 => Some(character - DIGITOFFSET),
-            65..=70 => Some(character - UPPERCASEOFFSET),
-            97..=102 => Some(character - LOWERCASEOFFSET),
-            _ => None,
-        }
+        CLASSIC_HEX_TABLE.get_index_for_char(character)
     }
+}
+
+/// Same digits as `ClassicHex`, but writes `a-f` the way `xxd` and most tools do
+pub struct LowercaseHex;
 
-    fn get_padding_char(&self) -> char {
-        unimplemented!()
+impl Alphabet for LowercaseHex {
+    fn get_char_for_index(&self, index: u8) -> Option<char> {
+        LOWERCASE_HEX_TABLE.get_char_for_index(index)
+    }
+
+    fn get_index_for_char(&self, character: char) -> Option<u8> {
+        LOWERCASE_HEX_TABLE.get_index_for_char(character)
     }
 }
 
@@ -55,4 +57,21 @@ mod tests {
             .collect();
         assert_eq!(result, "05AF")
     }
+
+    #[test]
+    fn should_convert_index_to_lowercase_text() {
+        let alphabet = LowercaseHex {};
+        let h: Vec<u8> = vec![0, 5, 10, 15];
+        let result: String = h
+            .iter()
+            .filter_map(|&n| alphabet.get_char_for_index(n))
+            .collect();
+        assert_eq!(result, "05af")
+    }
+
+    #[test]
+    fn should_reject_wide_characters() {
+        // U+0130 used to be cut down to '0'
+        assert_eq!(ClassicHex {}.get_index_for_char('\u{130}'), None);
+    }
 }
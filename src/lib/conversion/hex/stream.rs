@@ -0,0 +1,110 @@
+use std::io::{self, Read, Write};
+
+use super::model::ClassicHex;
+use crate::lib::conversion::alphabet::Alphabet;
+use crate::lib::conversion::radix::HEX;
+use crate::lib::conversion::stream::{RadixDecoder, RadixEncoder};
+
+/// Hex-encodes everything written to it into `W`.
+pub struct HexEncoder<W: Write, T: Alphabet = ClassicHex> {
+    inner: RadixEncoder<W, T>,
+}
+
+impl<W: Write> HexEncoder<W> {
+    pub fn new(writer: W) -> Self {
+        HexEncoder::with_alphabet(writer, ClassicHex {})
+    }
+}
+
+impl<W: Write, T: Alphabet> HexEncoder<W, T> {
+    pub fn with_alphabet(writer: W, alphabet: T) -> Self {
+        HexEncoder {
+            inner: RadixEncoder::new(writer, alphabet, HEX, false),
+        }
+    }
+
+    pub fn finish(self) -> io::Result<W> {
+        self.inner.finish()
+    }
+}
+
+impl<W: Write, T: Alphabet> Write for HexEncoder<W, T> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Reads hex text from `R`, skipping whitespace, and yields the decoded bytes.
+pub struct HexDecoder<R: Read, T: Alphabet = ClassicHex> {
+    inner: RadixDecoder<R, T>,
+}
+
+impl<R: Read> HexDecoder<R> {
+    pub fn new(reader: R) -> Self {
+        HexDecoder::with_alphabet(reader, ClassicHex {})
+    }
+}
+
+impl<R: Read, T: Alphabet> HexDecoder<R, T> {
+    pub fn with_alphabet(reader: R, alphabet: T) -> Self {
+        HexDecoder {
+            inner: RadixDecoder::new(reader, alphabet, HEX, false),
+        }
+    }
+}
+
+impl<R: Read, T: Alphabet> Read for HexDecoder<R, T> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lib::conversion::hex::{encode_lowercase, LowercaseHex};
+    use crate::lib::conversion::ConversionError;
+
+    #[test]
+    fn should_round_trip() {
+        let data: Vec<u8> = (0..=255_u8).cycle().take(10_000).collect();
+        let mut encoder = HexEncoder::with_alphabet(Vec::new(), LowercaseHex {});
+        io::copy(&mut data.as_slice(), &mut encoder).unwrap();
+        let encoded = encoder.finish().unwrap();
+        assert_eq!(encoded, encode_lowercase(&data).into_bytes());
+
+        let mut decoded = Vec::new();
+        HexDecoder::new(encoded.as_slice())
+            .read_to_end(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, data);
+    }
+
+    #[test]
+    fn should_decode_lines() {
+        let mut decoded = Vec::new();
+        HexDecoder::new("D00D\nCA BE\n".as_bytes())
+            .read_to_end(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, vec![0xD0, 0x0D, 0xCA, 0xBE]);
+    }
+
+    #[test]
+    fn should_fail_on_bad_input() {
+        let error = HexDecoder::new("D00DCAB".as_bytes())
+            .read_to_end(&mut Vec::new())
+            .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(
+            error
+                .into_inner()
+                .unwrap()
+                .downcast_ref::<ConversionError>(),
+            Some(&ConversionError::InvalidLength)
+        );
+    }
+}
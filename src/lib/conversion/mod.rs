@@ -1,6 +1,23 @@
 pub mod alphabet;
+pub mod base32;
+pub mod base58;
 pub mod base64;
+pub mod base85;
+pub mod binary;
+pub mod detect;
+pub mod error;
 pub mod hex;
+pub mod octal;
+pub mod pem;
+pub mod percent;
+pub mod quoted_printable;
+pub mod radix;
+pub mod stream;
+pub mod table;
+pub mod uuencode;
+
+pub use detect::{detect, Encoding};
+pub use error::ConversionError;
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,62 @@
+use super::alphabet::Alphabet;
+use super::radix::OCTAL;
+use super::ConversionError;
+
+const DIGITOFFSET: u8 = 48;
+
+pub struct OctalDigits;
+
+impl Alphabet for OctalDigits {
+    fn get_char_for_index(&self, index: u8) -> Option<char> {
+        match index {
+            0..=7 => Some((index + DIGITOFFSET) as char),
+            _ => None,
+        }
+    }
+
+    fn get_index_for_char(&self, character: char) -> Option<u8> {
+        match character {
+            '0'..='7' => Some(character as u8 - DIGITOFFSET),
+            _ => None,
+        }
+    }
+
+    fn get_padding_char(&self) -> Option<char> {
+        Some('=')
+    }
+}
+
+/// Pack the bits three at a time, so 3 bytes become 8 digits. Note this is
+/// a bit stream, not each byte printed in octal on its own.
+pub fn encode(data: &[u8]) -> String {
+    OCTAL
+        .encode(&OctalDigits {}, data, false)
+        .expect("OctalDigits holds all eight digits")
+}
+
+pub fn decode(data: &str) -> Result<Vec<u8>, ConversionError> {
+    OCTAL.decode(&OctalDigits {}, data, false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_convert_back_and_forth() {
+        let data = vec![0xFF, 0x00, 0xFF];
+        let encoded = encode(&data);
+        assert_eq!(encoded, "77600377");
+        assert_eq!(decode(&encoded).unwrap(), data);
+    }
+
+    #[test]
+    fn should_fail_gracefully() {
+        assert_eq!(decode("7777").unwrap_err(), ConversionError::InvalidLength);
+        assert_eq!(decode("777").unwrap_err(), ConversionError::TrailingBits);
+        assert_eq!(
+            decode("778").unwrap_err(),
+            ConversionError::InvalidCharacter { index: 2, ch: '8' }
+        );
+    }
+}
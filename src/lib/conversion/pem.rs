@@ -0,0 +1,292 @@
+/*
+
+PEM armor (RFC 7468), the base64 wrapping OpenSSL puts around keys and
+certificates. A file can hold any number of blocks with text in between,
+and old-style encrypted keys carry RFC 1421 headers such as `Proc-Type`
+and `DEK-Info` before a blank line and the body.
+
+*/
+use std::fmt;
+
+use super::base64::{self, PEM_LINE_WIDTH};
+use super::ConversionError;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pem {
+    /// What's between `BEGIN ` and the dashes, e.g. `CERTIFICATE`
+    pub label: String,
+    /// RFC 1421 headers in the order they appeared
+    pub headers: Vec<(String, String)>,
+    /// The decoded body, usually DER
+    pub contents: Vec<u8>,
+}
+
+impl Pem {
+    pub fn new(label: &str, contents: Vec<u8>) -> Pem {
+        Pem {
+            label: label.to_string(),
+            headers: Vec::new(),
+            contents,
+        }
+    }
+
+    /// Value of the first header called `name`, ignoring case
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Everything that can go wrong reading PEM. Line numbers count from 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PemError {
+    /// There's no `-----BEGIN` line at all
+    NoBlocks,
+    /// The input ran out before the block's `-----END` line
+    MissingEnd { label: String },
+    /// The `END` line names a different label than the `BEGIN` line
+    MismatchedLabel { begin: String, end: String },
+    /// A header line without a proper `Name: value` shape
+    InvalidHeader { line: usize },
+    /// The body isn't valid base64; an invalid character's index is its column
+    InvalidBase64 { line: usize, error: ConversionError },
+}
+
+impl fmt::Display for PemError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PemError::NoBlocks => write!(f, "no PEM block found"),
+            PemError::MissingEnd { label } => write!(f, "no END line for {:?}", label),
+            PemError::MismatchedLabel { begin, end } => {
+                write!(f, "BEGIN {:?} closed by END {:?}", begin, end)
+            }
+            PemError::InvalidHeader { line } => write!(f, "invalid header on line {}", line),
+            PemError::InvalidBase64 { line, error } => write!(f, "line {}: {}", line, error),
+        }
+    }
+}
+
+impl std::error::Error for PemError {}
+
+/// Read the first PEM block in `text`.
+pub fn parse(text: &str) -> Result<Pem, PemError> {
+    let mut lines = text.lines().enumerate();
+    while let Some((_, line)) = lines.next() {
+        if let Some(label) = boundary(line, "BEGIN") {
+            return parse_block(label, &mut lines);
+        }
+    }
+    Err(PemError::NoBlocks)
+}
+
+/// Read every PEM block in `text`, skipping whatever is around them.
+pub fn parse_many(text: &str) -> Result<Vec<Pem>, PemError> {
+    let mut blocks = Vec::new();
+    let mut lines = text.lines().enumerate();
+    while let Some((_, line)) = lines.next() {
+        if let Some(label) = boundary(line, "BEGIN") {
+            blocks.push(parse_block(label, &mut lines)?);
+        }
+    }
+    Ok(blocks)
+}
+
+/// The label of a `-----BEGIN label-----` or `-----END label-----` line
+fn boundary<'a>(line: &'a str, kind: &str) -> Option<&'a str> {
+    line.trim_end()
+        .strip_prefix("-----")?
+        .strip_prefix(kind)?
+        .strip_prefix(' ')?
+        .strip_suffix("-----")
+}
+
+fn parse_block<'a, I>(label: &str, lines: &mut I) -> Result<Pem, PemError>
+where
+    I: Iterator<Item = (usize, &'a str)>,
+{
+    let mut headers: Vec<(String, String)> = Vec::new();
+    let mut body: Vec<(usize, &str)> = Vec::new();
+    let mut in_headers = true;
+
+    for (number, line) in lines {
+        if let Some(end) = boundary(line, "END") {
+            if end != label {
+                return Err(PemError::MismatchedLabel {
+                    begin: label.to_string(),
+                    end: end.to_string(),
+                });
+            }
+            return Ok(Pem {
+                label: label.to_string(),
+                headers,
+                contents: decode_body(&body, number + 1)?,
+            });
+        }
+
+        if in_headers {
+            if line.starts_with([' ', '\t']) && !headers.is_empty() {
+                // a folded header goes on where the last one left off
+                let value = &mut headers.last_mut().unwrap().1;
+                value.push(' ');
+                value.push_str(line.trim());
+                continue;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                let name = name.trim();
+                if name.is_empty() || name.contains(char::is_whitespace) {
+                    return Err(PemError::InvalidHeader { line: number + 1 });
+                }
+                headers.push((name.to_string(), value.trim().to_string()));
+                continue;
+            }
+            in_headers = false;
+        }
+
+        body.push((number + 1, line));
+    }
+
+    Err(PemError::MissingEnd {
+        label: label.to_string(),
+    })
+}
+
+fn decode_body(body: &[(usize, &str)], end_line: usize) -> Result<Vec<u8>, PemError> {
+    let text: Vec<&str> = body.iter().map(|&(_, line)| line).collect();
+    base64::decode_lenient(&text.join("\n")).map_err(|error| match error {
+        // find the line the character sits on
+        ConversionError::InvalidCharacter { mut index, ch } => {
+            let mut line = end_line;
+            for &(number, content) in body {
+                let length = content.chars().count();
+                if index < length {
+                    line = number;
+                    break;
+                }
+                index -= length + 1;
+            }
+            PemError::InvalidBase64 {
+                line,
+                error: ConversionError::InvalidCharacter { index, ch },
+            }
+        }
+        _ => PemError::InvalidBase64 {
+            line: end_line,
+            error,
+        },
+    })
+}
+
+/// Write `pem` out the way OpenSSL does: headers, a blank line if there were
+/// any, then the body in 64-column lines.
+pub fn encode(pem: &Pem) -> String {
+    let mut out = format!("-----BEGIN {}-----\n", pem.label);
+    for (name, value) in &pem.headers {
+        out.push_str(&format!("{}: {}\n", name, value));
+    }
+    if !pem.headers.is_empty() {
+        out.push('\n');
+    }
+
+    let body = base64::encode_wrapped(&pem.contents, PEM_LINE_WIDTH);
+    if !body.is_empty() {
+        out.push_str(&body);
+        out.push('\n');
+    }
+    out.push_str(&format!("-----END {}-----\n", pem.label));
+    out
+}
+
+pub fn encode_many(blocks: &[Pem]) -> String {
+    blocks.iter().map(encode).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CERTIFICATE: &str = include_str!("../../../files/pem/cert.pem");
+    const CERTIFICATE_DER: &[u8] = include_bytes!("../../../files/pem/cert.der");
+    const ENCRYPTED_KEY: &str = include_str!("../../../files/pem/key-encrypted.pem");
+    const BUNDLE: &str = include_str!("../../../files/pem/bundle.pem");
+
+    #[test]
+    fn should_read_an_openssl_certificate() {
+        let pem = parse(CERTIFICATE).unwrap();
+        assert_eq!(pem.label, "CERTIFICATE");
+        assert!(pem.headers.is_empty());
+        assert_eq!(pem.contents, CERTIFICATE_DER);
+        assert_eq!(encode(&pem), CERTIFICATE);
+    }
+
+    #[test]
+    fn should_keep_rfc_1421_headers() {
+        let pem = parse(ENCRYPTED_KEY).unwrap();
+        assert_eq!(pem.label, "EC PRIVATE KEY");
+        assert_eq!(pem.header("proc-type"), Some("4,ENCRYPTED"));
+        assert_eq!(
+            pem.header("DEK-Info"),
+            Some("AES-128-CBC,D01A07ACE48494F4D2511CBC3BCFB780")
+        );
+        assert_eq!(pem.contents.len(), 128);
+        assert_eq!(encode(&pem), ENCRYPTED_KEY);
+    }
+
+    #[test]
+    fn should_read_every_block_in_a_bundle() {
+        let blocks = parse_many(BUNDLE).unwrap();
+        let labels: Vec<&str> = blocks.iter().map(|pem| pem.label.as_str()).collect();
+        assert_eq!(labels, vec!["CERTIFICATE", "EC PRIVATE KEY"]);
+        assert_eq!(blocks[0].contents, CERTIFICATE_DER);
+        assert!(blocks[1].contents.starts_with(b"Not a real key"));
+        assert_eq!(parse_many(&encode_many(&blocks)).unwrap(), blocks);
+        assert_eq!(parse_many("no armor here").unwrap(), vec![]);
+    }
+
+    #[test]
+    fn should_accept_crlf_and_folded_headers() {
+        let text = "-----BEGIN MESSAGE-----\r\nComment: one\r\n  two\r\n\r\nYWJj\r\nZA==\r\n-----END MESSAGE-----\r\n";
+        let pem = parse(text).unwrap();
+        assert_eq!(pem.header("Comment"), Some("one two"));
+        assert_eq!(pem.contents, b"abcd");
+    }
+
+    #[test]
+    fn should_emit_64_columns() {
+        let pem = Pem::new("DATA", vec![0; 100]);
+        let text = encode(&pem);
+        let widths: Vec<usize> = text.lines().map(str::len).collect();
+        assert_eq!(widths, vec![20, 64, 64, 8, 18]);
+        assert_eq!(parse(&text).unwrap(), pem);
+    }
+
+    #[test]
+    fn should_say_what_is_wrong() {
+        assert_eq!(parse("").unwrap_err(), PemError::NoBlocks);
+        assert_eq!(
+            parse("-----BEGIN A-----\nYWJj\n").unwrap_err(),
+            PemError::MissingEnd {
+                label: "A".to_string()
+            }
+        );
+        assert_eq!(
+            parse("-----BEGIN A-----\nYWJj\n-----END B-----").unwrap_err(),
+            PemError::MismatchedLabel {
+                begin: "A".to_string(),
+                end: "B".to_string()
+            }
+        );
+        assert_eq!(
+            parse("-----BEGIN A-----\nBad Name: x\n\nYWJj\n-----END A-----").unwrap_err(),
+            PemError::InvalidHeader { line: 2 }
+        );
+        assert_eq!(
+            parse("-----BEGIN A-----\nYWJj\nYW!j\n-----END A-----").unwrap_err(),
+            PemError::InvalidBase64 {
+                line: 3,
+                error: ConversionError::InvalidCharacter { index: 2, ch: '!' }
+            }
+        );
+    }
+}
@@ -0,0 +1,140 @@
+/*
+
+Percent-encoding (RFC 3986 §2.1), as found in URLs and form posts. Only the
+unreserved characters are left alone when encoding. Strict decoding takes
+exactly what may appear in a URI; lenient decoding reads HTML form data
+(`+` for space) and passes anything it doesn't understand through as is.
+
+*/
+use super::ConversionError;
+
+fn is_unreserved(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~')
+}
+
+/// Characters RFC 3986 allows in a URI besides `%` escapes
+fn is_uri_character(character: char) -> bool {
+    character.is_ascii()
+        && (is_unreserved(character as u8) || ":/?#[]@!$&'()*+,;=".contains(character))
+}
+
+pub fn encode(data: &[u8]) -> String {
+    let mut out = String::with_capacity(data.len() * 3);
+    for &byte in data {
+        match is_unreserved(byte) {
+            true => out.push(byte as char),
+            false => out.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    out
+}
+
+/// Encode for an `application/x-www-form-urlencoded` body: spaces become `+`.
+pub fn encode_form(data: &[u8]) -> String {
+    encode(data).replace("%20", "+")
+}
+
+/// Decode a URI component. Every `%` has to start a two digit escape and
+/// nothing outside the URI character set may appear.
+pub fn decode(text: &str) -> Result<Vec<u8>, ConversionError> {
+    let characters: Vec<char> = text.chars().collect();
+    let mut out = Vec::with_capacity(characters.len());
+
+    let mut i = 0;
+    while i < characters.len() {
+        match characters[i] {
+            '%' => {
+                out.push(escaped_byte(&characters, i)?);
+                i += 3;
+            }
+            ch if is_uri_character(ch) => {
+                out.push(ch as u8);
+                i += 1;
+            }
+            ch => return Err(ConversionError::InvalidCharacter { index: i, ch }),
+        }
+    }
+
+    Ok(out)
+}
+
+/// Decode form data or a hand-mangled URL: `+` is a space, a `%` that doesn't
+/// start an escape stays a `%`, and other characters come out as their UTF-8.
+pub fn decode_lenient(text: &str) -> Vec<u8> {
+    let characters: Vec<char> = text.chars().collect();
+    let mut out = Vec::with_capacity(text.len());
+
+    let mut i = 0;
+    while i < characters.len() {
+        match (characters[i], escaped_byte(&characters, i)) {
+            ('%', Ok(byte)) => {
+                out.push(byte);
+                i += 3;
+                continue;
+            }
+            ('+', _) => out.push(b' '),
+            (ch, _) => out.extend_from_slice(ch.encode_utf8(&mut [0; 4]).as_bytes()),
+        }
+        i += 1;
+    }
+
+    out
+}
+
+/// The byte a `%XX` escape starting at `start` stands for
+fn escaped_byte(characters: &[char], start: usize) -> Result<u8, ConversionError> {
+    (start + 1..start + 3).try_fold(0_u8, |byte, index| {
+        let ch = *characters
+            .get(index)
+            .ok_or(ConversionError::InvalidLength)?;
+        let digit = ch
+            .to_digit(16)
+            .ok_or(ConversionError::InvalidCharacter { index, ch })?;
+        Ok(byte << 4 | digit as u8)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_convert_back_and_forth() {
+        let data: Vec<u8> = (0..=255_u8).collect();
+        let encoded = encode(&data);
+        assert!(encoded.starts_with("%00%01%02"));
+        assert!(encoded.contains("-.%2F0123456789%3A"));
+        assert_eq!(decode(&encoded).unwrap(), data);
+        assert_eq!(decode_lenient(&encoded), data);
+    }
+
+    #[test]
+    fn should_decode_urls() {
+        assert_eq!(
+            decode("user%3Dadmin;role=%61dmin").unwrap(),
+            b"user=admin;role=admin"
+        );
+        assert_eq!(decode("a+b").unwrap(), b"a+b");
+    }
+
+    #[test]
+    fn should_decode_form_data_leniently() {
+        assert_eq!(encode_form(b"comment=a b&c"), "comment%3Da+b%26c");
+        assert_eq!(decode_lenient("a+b%20c"), b"a b c");
+        assert_eq!(decode_lenient("100% sure %zz%4"), b"100% sure %zz%4");
+        assert_eq!(decode_lenient("caf\u{e9}"), "caf\u{e9}".as_bytes());
+    }
+
+    #[test]
+    fn should_be_strict_about_escapes() {
+        assert_eq!(
+            decode("100%zz").unwrap_err(),
+            ConversionError::InvalidCharacter { index: 4, ch: 'z' }
+        );
+        assert_eq!(decode("%4").unwrap_err(), ConversionError::InvalidLength);
+        assert_eq!(
+            decode("a b").unwrap_err(),
+            ConversionError::InvalidCharacter { index: 1, ch: ' ' }
+        );
+    }
+}
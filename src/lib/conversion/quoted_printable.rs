@@ -0,0 +1,226 @@
+/*
+
+Quoted-printable (RFC 2045 §6.7), the mail body encoding that leaves
+printable ASCII readable and writes everything else as `=XX`. Lines are
+kept to 76 characters with soft breaks (`=` at the end of a line), and
+whitespace at the end of a line is dropped, since mail relays add and
+strip it freely.
+
+*/
+use super::ConversionError;
+
+/// Longest line allowed, soft break included
+pub const LINE_WIDTH: usize = 76;
+
+/// CRLF pairs in `data` stay hard line breaks, a lone CR or LF is escaped, so
+/// any bytes come back unchanged.
+pub fn encode(data: &[u8]) -> String {
+    let mut out = String::with_capacity(data.len() * 3);
+    let mut line_length = 0;
+
+    let mut i = 0;
+    while i < data.len() {
+        let byte = data[i];
+        if data[i..].starts_with(b"\r\n") {
+            out.push_str("\r\n");
+            line_length = 0;
+            i += 2;
+            continue;
+        }
+
+        // whitespace right before a line break would get eaten on the way
+        let ends_line = i + 1 == data.len() || data[i + 1..].starts_with(b"\r\n");
+        let literal = match byte {
+            b' ' | b'\t' => !ends_line,
+            b'=' => false,
+            _ => byte.is_ascii_graphic(),
+        };
+        let token = match literal {
+            true => (byte as char).to_string(),
+            false => format!("={:02X}", byte),
+        };
+
+        // leave room on the line for the soft break's `=`
+        if line_length + token.len() > LINE_WIDTH - 1 {
+            out.push_str("=\r\n");
+            line_length = 0;
+        }
+        out.push_str(&token);
+        line_length += token.len();
+        i += 1;
+    }
+
+    out
+}
+
+/// Decode RFC 2045 conformant input: uppercase escapes, CRLF line breaks
+/// and nothing but printable ASCII, spaces and tabs in between.
+pub fn decode(text: &str) -> Result<Vec<u8>, ConversionError> {
+    decode_with(text, false)
+}
+
+/// Decode whatever mail clients actually send: lowercase escapes, bare LF
+/// line breaks, a stray `=` taken literally and non-ASCII passed through.
+pub fn decode_lenient(text: &str) -> Result<Vec<u8>, ConversionError> {
+    decode_with(text, true)
+}
+
+fn decode_with(text: &str, lenient: bool) -> Result<Vec<u8>, ConversionError> {
+    let characters: Vec<char> = text.chars().collect();
+    let mut out = Vec::with_capacity(characters.len());
+    // spaces and tabs, held back until we know they aren't trailing
+    let mut whitespace: Vec<u8> = Vec::new();
+
+    let mut i = 0;
+    while i < characters.len() {
+        let line_break = match characters[i..] {
+            ['\r', '\n', ..] => 2,
+            ['\n', ..] if lenient => 1,
+            _ => 0,
+        };
+        if line_break > 0 {
+            whitespace.clear();
+            out.extend(characters[i..i + line_break].iter().map(|&c| c as u8));
+            i += line_break;
+            continue;
+        }
+
+        match characters[i] {
+            ' ' | '\t' => {
+                whitespace.push(characters[i] as u8);
+                i += 1;
+            }
+            '=' => {
+                out.append(&mut whitespace);
+
+                // a soft break, possibly with whitespace before the line ends
+                let mut end = i + 1;
+                while matches!(characters.get(end), Some(' ' | '\t')) {
+                    end += 1;
+                }
+                match characters[end..] {
+                    [] => {
+                        i = end;
+                        continue;
+                    }
+                    ['\r', '\n', ..] => {
+                        i = end + 2;
+                        continue;
+                    }
+                    ['\n', ..] if lenient => {
+                        i = end + 1;
+                        continue;
+                    }
+                    _ => (),
+                }
+
+                match escaped_byte(&characters, i, lenient) {
+                    Ok(byte) => {
+                        out.push(byte);
+                        i += 3;
+                    }
+                    Err(_) if lenient => {
+                        out.push(b'=');
+                        i += 1;
+                    }
+                    Err(error) => return Err(error),
+                }
+            }
+            ch if ch.is_ascii_graphic() => {
+                out.append(&mut whitespace);
+                out.push(ch as u8);
+                i += 1;
+            }
+            ch if lenient => {
+                out.append(&mut whitespace);
+                out.extend_from_slice(ch.encode_utf8(&mut [0; 4]).as_bytes());
+                i += 1;
+            }
+            ch => return Err(ConversionError::InvalidCharacter { index: i, ch }),
+        }
+    }
+
+    Ok(out)
+}
+
+/// The byte an `=XX` escape starting at `start` stands for
+fn escaped_byte(characters: &[char], start: usize, lenient: bool) -> Result<u8, ConversionError> {
+    (start + 1..start + 3).try_fold(0_u8, |byte, index| {
+        let ch = *characters
+            .get(index)
+            .ok_or(ConversionError::InvalidLength)?;
+        let digit = match ch {
+            '0'..='9' | 'A'..='F' => ch.to_digit(16),
+            'a'..='f' if lenient => ch.to_digit(16),
+            _ => None,
+        };
+        let digit = digit.ok_or(ConversionError::InvalidCharacter { index, ch })?;
+        Ok(byte << 4 | digit as u8)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_convert_back_and_forth() {
+        let data: Vec<u8> = (0..=255_u8).cycle().take(1000).collect();
+        let encoded = encode(&data);
+        assert!(encoded.split("\r\n").all(|line| line.len() <= LINE_WIDTH));
+        assert_eq!(decode(&encoded).unwrap(), data);
+        assert_eq!(decode_lenient(&encoded).unwrap(), data);
+    }
+
+    #[test]
+    fn should_keep_text_readable() {
+        let text = "Caf\u{e9} = 5\u{20ac}\r\nsee you \r\n";
+        let encoded = encode(text.as_bytes());
+        assert_eq!(encoded, "Caf=C3=A9 =3D 5=E2=82=AC\r\nsee you=20\r\n");
+        assert_eq!(decode(&encoded).unwrap(), text.as_bytes());
+    }
+
+    #[test]
+    fn should_wrap_long_lines() {
+        let encoded = encode(&[b'a'; 100]);
+        assert_eq!(
+            encoded,
+            format!("{}=\r\n{}", "a".repeat(75), "a".repeat(25))
+        );
+        assert_eq!(decode(&encoded).unwrap(), vec![b'a'; 100]);
+    }
+
+    #[test]
+    fn should_drop_trailing_whitespace() {
+        assert_eq!(decode("one  \r\ntwo \t").unwrap(), b"one\r\ntwo");
+        assert_eq!(decode("soft  = \r\nbreak").unwrap(), b"soft  break");
+    }
+
+    #[test]
+    fn should_be_strict_about_escapes_and_line_breaks() {
+        assert_eq!(
+            decode("=e9").unwrap_err(),
+            ConversionError::InvalidCharacter { index: 1, ch: 'e' }
+        );
+        assert_eq!(decode("a=3").unwrap_err(), ConversionError::InvalidLength);
+        assert_eq!(
+            decode("one\ntwo").unwrap_err(),
+            ConversionError::InvalidCharacter { index: 3, ch: '\n' }
+        );
+        assert_eq!(
+            decode("caf\u{e9}").unwrap_err(),
+            ConversionError::InvalidCharacter {
+                index: 3,
+                ch: '\u{e9}'
+            }
+        );
+    }
+
+    #[test]
+    fn should_read_sloppy_mail_leniently() {
+        assert_eq!(
+            decode_lenient("caf=c3=a9 =\nau lait\n100=%").unwrap(),
+            "caf\u{e9} au lait\n100=%".as_bytes()
+        );
+    }
+}
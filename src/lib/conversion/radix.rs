@@ -0,0 +1,551 @@
+/*
+
+One bit-packing engine for every power-of-two base. Symbols carrying
+`bits_per_symbol` bits are packed big-endian into groups that line up with
+whole bytes again: 2 hex digits make a byte, 4 base64 symbols make 3 bytes,
+8 base32 symbols make 5 bytes and so on. A short final group is zero-filled
+on the way out and, optionally, padded up to full size.
+
+*/
+use super::alphabet::{check_symbol_count, Alphabet};
+use super::ConversionError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Radix {
+    bits_per_symbol: u8,
+}
+
+pub const BINARY: Radix = Radix { bits_per_symbol: 1 };
+pub const OCTAL: Radix = Radix { bits_per_symbol: 3 };
+pub const HEX: Radix = Radix { bits_per_symbol: 4 };
+pub const BASE32: Radix = Radix { bits_per_symbol: 5 };
+pub const BASE64: Radix = Radix { bits_per_symbol: 6 };
+
+/// Widest group there is: base32 and octal use eight symbols
+const MAX_SYMBOLS_PER_GROUP: usize = 8;
+
+impl Radix {
+    /// Anything from binary (1 bit) up to base64 (6 bits) is supported
+    pub fn new(bits_per_symbol: u8) -> Option<Radix> {
+        match bits_per_symbol {
+            1..=6 => Some(Radix { bits_per_symbol }),
+            _ => None,
+        }
+    }
+
+    pub fn bits_per_symbol(&self) -> u8 {
+        self.bits_per_symbol
+    }
+
+    /// Symbols in one full group
+    pub fn symbols_per_group(&self) -> usize {
+        self.group_bits() / self.bits_per_symbol as usize
+    }
+
+    /// Bytes in one full group
+    pub fn bytes_per_group(&self) -> usize {
+        self.group_bits() / 8
+    }
+
+    /// Smallest bit count that's a whole number of both bytes and symbols
+    fn group_bits(&self) -> usize {
+        let bits = self.bits_per_symbol as usize;
+        let mut gcd = (8, bits);
+        while gcd.1 != 0 {
+            gcd = (gcd.1, gcd.0 % gcd.1);
+        }
+        8 * bits / gcd.0
+    }
+
+    /// Symbols needed for `bytes` bytes, not counting padding
+    fn symbols_for_bytes(&self, bytes: usize) -> usize {
+        (bytes * 8).div_ceil(self.bits_per_symbol as usize)
+    }
+
+    /// Whole bytes held by `symbols` symbols, if an encoder could have produced that count
+    fn bytes_for_symbols(&self, symbols: usize) -> Option<usize> {
+        let bytes = symbols * self.bits_per_symbol as usize / 8;
+        (self.symbols_for_bytes(bytes) == symbols).then_some(bytes)
+    }
+
+    /// Only groups shorter than a byte-multiple can come up short, and need padding
+    fn uses_padding(&self) -> bool {
+        self.bytes_per_group() > 1
+    }
+
+    /// Characters `encode` produces for `bytes` bytes
+    pub fn encoded_len(&self, bytes: usize, padded: bool) -> usize {
+        match padded && self.uses_padding() {
+            true => bytes.div_ceil(self.bytes_per_group()) * self.symbols_per_group(),
+            false => self.symbols_for_bytes(bytes),
+        }
+    }
+
+    /// Bytes that decoding `data` will produce, padded or not, without decoding it.
+    /// Only the length is checked, the characters themselves may still be invalid.
+    pub fn decoded_len<T: Alphabet>(
+        &self,
+        alphabet: &T,
+        data: &str,
+    ) -> Result<usize, ConversionError> {
+        let padding = match (self.uses_padding(), alphabet.get_padding_char()) {
+            (true, Some(padding_char)) => data
+                .chars()
+                .rev()
+                .take_while(|&c| c == padding_char)
+                .count(),
+            _ => 0,
+        };
+        self.bytes_for_symbols(data.chars().count() - padding)
+            .ok_or(ConversionError::InvalidLength)
+    }
+
+    /// Check `alphabet` has a symbol for every index, and hand back the
+    /// padding character if `padded` calls for one. Asking an alphabet
+    /// without padding for it is an error, but unpadded use never looks.
+    fn check_alphabet<T: Alphabet>(
+        &self,
+        alphabet: &T,
+        padded: bool,
+    ) -> Result<Option<char>, ConversionError> {
+        check_symbol_count(alphabet, 1 << self.bits_per_symbol)?;
+        if !padded || !self.uses_padding() {
+            return Ok(None);
+        }
+        alphabet
+            .get_padding_char()
+            .map(Some)
+            .ok_or(ConversionError::NoPaddingCharacter)
+    }
+
+    pub fn encode<T: Alphabet>(
+        &self,
+        alphabet: &T,
+        data: &[u8],
+        padded: bool,
+    ) -> Result<String, ConversionError> {
+        let groups = data.len().div_ceil(self.bytes_per_group());
+        let mut out = String::with_capacity(groups * self.symbols_per_group());
+        self.encode_with(alphabet, data, padded, |character| out.push(character))?;
+        Ok(out)
+    }
+
+    /// Encode `data`, handing each output character to `emit` as it's produced.
+    /// The alphabet is checked first, so nothing is emitted if it doesn't fit.
+    pub fn encode_with<T: Alphabet, F: FnMut(char)>(
+        &self,
+        alphabet: &T,
+        data: &[u8],
+        padded: bool,
+        mut emit: F,
+    ) -> Result<(), ConversionError> {
+        let padding = self.check_alphabet(alphabet, padded)?;
+        let bits = self.bits_per_symbol as usize;
+        let group_bits = self.group_bits();
+        let mask = (1_u64 << bits) - 1;
+
+        for chunk in data.chunks(self.bytes_per_group()) {
+            // line the bytes up at the top of the group, zeroes fill the rest
+            let group = chunk.iter().enumerate().fold(0_u64, |group, (i, &byte)| {
+                group | (byte as u64) << (group_bits - 8 * (i + 1))
+            });
+
+            let symbols = self.symbols_for_bytes(chunk.len());
+            for i in 0..symbols {
+                let index = (group >> (group_bits - bits * (i + 1))) & mask;
+                let character = alphabet
+                    .get_char_for_index(index as u8)
+                    .ok_or(ConversionError::WrongAlphabetSize { needed: 1 << bits })?;
+                emit(character);
+            }
+
+            if let Some(padding) = padding {
+                (symbols..self.symbols_per_group()).for_each(|_| emit(padding));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Encode into the front of `out` and return how many bytes were written.
+    /// The alphabet has to be ASCII, one byte per character, so that
+    /// `encoded_len` is a byte count; any other symbol is an error.
+    pub fn encode_to_slice<T: Alphabet>(
+        &self,
+        alphabet: &T,
+        data: &[u8],
+        padded: bool,
+        out: &mut [u8],
+    ) -> Result<usize, ConversionError> {
+        let needed = self.encoded_len(data.len(), padded);
+        if out.len() < needed {
+            return Err(ConversionError::BufferTooSmall {
+                needed,
+                available: out.len(),
+            });
+        }
+
+        let padding = self.check_alphabet(alphabet, padded)?;
+        let mut symbols = (0..1_u16 << self.bits_per_symbol)
+            .filter_map(|index| alphabet.get_char_for_index(index as u8))
+            .chain(padding);
+        if let Some(ch) = symbols.find(|ch| !ch.is_ascii()) {
+            return Err(ConversionError::NonAsciiSymbol { ch });
+        }
+
+        let mut written = 0;
+        self.encode_with(alphabet, data, padded, |character| {
+            out[written] = character as u8;
+            written += 1;
+        })?;
+        Ok(written)
+    }
+
+    pub fn decode<T: Alphabet>(
+        &self,
+        alphabet: &T,
+        data: &str,
+        padded: bool,
+    ) -> Result<Vec<u8>, ConversionError> {
+        let length = data.chars().count();
+        let mut out = Vec::with_capacity(length * self.bits_per_symbol as usize / 8);
+        self.decode_with(alphabet, data.chars(), length, padded, |byte| {
+            out.push(byte)
+        })?;
+        Ok(out)
+    }
+
+    /// Decode into the front of `out` and return how many bytes were written.
+    pub fn decode_to_slice<T: Alphabet>(
+        &self,
+        alphabet: &T,
+        data: &str,
+        padded: bool,
+        out: &mut [u8],
+    ) -> Result<usize, ConversionError> {
+        let length = data.chars().count();
+        let needed = match self.decoded_len(alphabet, data) {
+            Ok(needed) => needed,
+            // report whatever is wrong the same way `decode` would
+            Err(error) => {
+                let result = self.decode_with(alphabet, data.chars(), length, padded, |_| ());
+                return Err(result.err().unwrap_or(error));
+            }
+        };
+        if out.len() < needed {
+            return Err(ConversionError::BufferTooSmall {
+                needed,
+                available: out.len(),
+            });
+        }
+
+        let mut written = 0;
+        self.decode_with(alphabet, data.chars(), length, padded, |byte| {
+            out[written] = byte;
+            written += 1;
+        })?;
+        Ok(written)
+    }
+
+    /// Decode `length` characters, handing each byte to `emit` as it's produced.
+    /// Errors carry the index of the character within `characters`.
+    pub fn decode_with<T, I, F>(
+        &self,
+        alphabet: &T,
+        mut characters: I,
+        length: usize,
+        padded: bool,
+        mut emit: F,
+    ) -> Result<(), ConversionError>
+    where
+        T: Alphabet,
+        I: Iterator<Item = char>,
+        F: FnMut(u8),
+    {
+        let bits = self.bits_per_symbol as usize;
+        let symbols_per_group = self.symbols_per_group();
+        let padding = self.check_alphabet(alphabet, padded)?;
+
+        let partial = length % symbols_per_group;
+        if (padded && partial != 0) || self.bytes_for_symbols(partial).is_none() {
+            return Err(ConversionError::InvalidLength);
+        }
+
+        let groups = length.div_ceil(symbols_per_group);
+        let mut group_chars = ['\0'; MAX_SYMBOLS_PER_GROUP];
+
+        for group in 0..groups {
+            let offset = group * symbols_per_group;
+            let chunk = &mut group_chars[..symbols_per_group.min(length - offset)];
+            for slot in chunk.iter_mut() {
+                *slot = characters.next().ok_or(ConversionError::InvalidLength)?;
+            }
+
+            let symbols = chunk
+                .iter()
+                .take_while(|&&character| Some(character) != padding)
+                .count();
+
+            // padding only ever ends the final group, and never eats a whole byte
+            if symbols < chunk.len()
+                && (!padded
+                    || group + 1 != groups
+                    || symbols == 0
+                    || self.bytes_for_symbols(symbols).is_none()
+                    || chunk[symbols..].iter().any(|&c| Some(c) != padding))
+            {
+                return Err(ConversionError::InvalidPadding);
+            }
+
+            let mut value = 0_u64;
+            for (i, &ch) in chunk[..symbols].iter().enumerate() {
+                // padding where none was asked for is still reported as padding
+                let index = alphabet.get_index_for_char(ch).ok_or_else(|| {
+                    match Some(ch) == alphabet.get_padding_char() {
+                        true => ConversionError::InvalidPadding,
+                        false => ConversionError::InvalidCharacter {
+                            index: offset + i,
+                            ch,
+                        },
+                    }
+                })?;
+                value = value << bits | (index as u64 & ((1 << bits) - 1));
+            }
+
+            // a short group ends in filler bits, which an encoder leaves at zero
+            let bytes = symbols * bits / 8;
+            let filler = symbols * bits - bytes * 8;
+            if value & ((1 << filler) - 1) != 0 {
+                return Err(ConversionError::TrailingBits);
+            }
+
+            let value = value >> filler;
+            (0..bytes).for_each(|i| emit((value >> (8 * (bytes - 1 - i))) as u8));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lib::conversion::alphabet::CustomAlphabet;
+    use crate::lib::conversion::base32::ClassicBase32;
+    use crate::lib::conversion::base64::ClassicBase64;
+    use crate::lib::conversion::binary::BinaryDigits;
+    use crate::lib::conversion::hex::ClassicHex;
+    use crate::lib::conversion::octal::OctalDigits;
+
+    #[test]
+    fn should_size_groups() {
+        let sizes: Vec<(usize, usize)> = [BINARY, OCTAL, HEX, BASE32, BASE64]
+            .iter()
+            .map(|radix| (radix.symbols_per_group(), radix.bytes_per_group()))
+            .collect();
+        assert_eq!(sizes, vec![(8, 1), (8, 3), (2, 1), (8, 5), (4, 3)]);
+        assert_eq!(Radix::new(7), None);
+        assert_eq!(Radix::new(4), Some(HEX));
+    }
+
+    #[test]
+    fn should_split_bytes() {
+        assert_eq!(HEX.encode(&ClassicHex {}, &[0xDF], false).unwrap(), "DF");
+        assert_eq!(
+            BINARY.encode(&BinaryDigits {}, &[0xDF], false).unwrap(),
+            "11011111"
+        );
+        assert_eq!(
+            OCTAL.encode(&OctalDigits {}, &[0xFF], false).unwrap(),
+            "776"
+        );
+        assert_eq!(
+            BASE32.encode(&ClassicBase32 {}, &[0xFF], false).unwrap(),
+            "74"
+        );
+        assert_eq!(
+            BASE64.encode(&ClassicBase64 {}, &[0xFF], true).unwrap(),
+            "/w=="
+        );
+    }
+
+    #[test]
+    fn should_combine_bytes() {
+        assert_eq!(HEX.decode(&ClassicHex {}, "DF", false).unwrap(), vec![0xDF]);
+        assert_eq!(
+            BINARY.decode(&BinaryDigits {}, "11011111", false).unwrap(),
+            vec![0xDF]
+        );
+        assert_eq!(
+            OCTAL.decode(&OctalDigits {}, "776", false).unwrap(),
+            vec![0xFF]
+        );
+    }
+
+    #[test]
+    fn should_only_accept_lengths_an_encoder_makes() {
+        let octal = &OctalDigits {};
+        for length in 0..=9 {
+            let symbols = "0".repeat(length);
+            let valid = matches!(length, 0 | 3 | 6 | 8);
+            assert_eq!(
+                OCTAL.decode(octal, &symbols, false).is_ok(),
+                valid,
+                "{}",
+                length
+            );
+        }
+    }
+
+    /// Classic base64 minus its padding
+    struct Unpadded;
+
+    impl Alphabet for Unpadded {
+        fn get_char_for_index(&self, index: u8) -> Option<char> {
+            ClassicBase64.get_char_for_index(index)
+        }
+
+        fn get_index_for_char(&self, character: char) -> Option<u8> {
+            ClassicBase64.get_index_for_char(character)
+        }
+    }
+
+    #[test]
+    fn should_only_need_padding_when_padded() {
+        assert_eq!(BASE64.encode(&Unpadded, b"hi", false).unwrap(), "aGk");
+        assert_eq!(BASE64.decode(&Unpadded, "aGk", false).unwrap(), b"hi");
+        assert_eq!(
+            BASE64.encode(&Unpadded, b"hi", true),
+            Err(ConversionError::NoPaddingCharacter)
+        );
+        assert_eq!(
+            BASE64.decode(&Unpadded, "aGk=", true),
+            Err(ConversionError::NoPaddingCharacter)
+        );
+        // hex never pads, so there's nothing to ask for
+        assert_eq!(HEX.encode(&ClassicHex {}, b"hi", true).unwrap(), "6869");
+    }
+
+    #[test]
+    fn should_refuse_alphabets_of_the_wrong_size() {
+        assert_eq!(
+            BASE64.encode(&ClassicHex {}, b"\xff\xff\xff", false),
+            Err(ConversionError::WrongAlphabetSize { needed: 64 })
+        );
+        assert_eq!(
+            HEX.encode(&ClassicBase64 {}, b"\xff", false),
+            Err(ConversionError::WrongAlphabetSize { needed: 16 })
+        );
+        assert_eq!(
+            HEX.decode(&ClassicBase64 {}, "ff", false),
+            Err(ConversionError::WrongAlphabetSize { needed: 16 })
+        );
+    }
+
+    #[test]
+    fn should_know_lengths_up_front() {
+        let data: Vec<u8> = (0..=255_u8).collect();
+        for radix in [BINARY, OCTAL, HEX, BASE32, BASE64] {
+            for length in 0..=16 {
+                for padded in [false, true] {
+                    let encoded = match radix {
+                        BINARY => radix.encode(&BinaryDigits {}, &data[..length], padded),
+                        OCTAL => radix.encode(&OctalDigits {}, &data[..length], padded),
+                        HEX => radix.encode(&ClassicHex {}, &data[..length], padded),
+                        BASE32 => radix.encode(&ClassicBase32 {}, &data[..length], padded),
+                        _ => radix.encode(&ClassicBase64 {}, &data[..length], padded),
+                    };
+                    assert_eq!(radix.encoded_len(length, padded), encoded.unwrap().len());
+                }
+            }
+        }
+
+        let base64 = &ClassicBase64 {};
+        assert_eq!(BASE64.decoded_len(base64, "YWJjZA=="), Ok(4));
+        assert_eq!(BASE64.decoded_len(base64, "YWJjZA"), Ok(4));
+        assert_eq!(
+            BASE64.decoded_len(base64, "YWJjZ"),
+            Err(ConversionError::InvalidLength)
+        );
+        assert_eq!(OCTAL.decoded_len(&OctalDigits {}, "77777777"), Ok(3));
+    }
+
+    #[test]
+    fn should_fill_caller_buffers() {
+        let base64 = &ClassicBase64 {};
+        let mut buffer = [0_u8; 8];
+
+        assert_eq!(
+            BASE64.encode_to_slice(base64, b"abcd", true, &mut buffer),
+            Ok(8)
+        );
+        assert_eq!(&buffer, b"YWJjZA==");
+        assert_eq!(
+            BASE64.encode_to_slice(base64, b"abcdefg", true, &mut buffer),
+            Err(ConversionError::BufferTooSmall {
+                needed: 12,
+                available: 8
+            })
+        );
+
+        assert_eq!(
+            BASE64.decode_to_slice(base64, "YWJjZA==", true, &mut buffer),
+            Ok(4)
+        );
+        assert_eq!(&buffer[..4], b"abcd");
+        assert_eq!(
+            BASE64.decode_to_slice(base64, "YWJjZA==", true, &mut buffer[..3]),
+            Err(ConversionError::BufferTooSmall {
+                needed: 4,
+                available: 3
+            })
+        );
+        assert_eq!(
+            BASE64.decode_to_slice(base64, "Y===", true, &mut buffer),
+            Err(ConversionError::InvalidPadding)
+        );
+    }
+
+    #[test]
+    fn should_refuse_to_squeeze_symbols_into_bytes() {
+        let greek = &CustomAlphabet::new("αβγδ", None).unwrap();
+        let mut buffer = [0_u8; 8];
+        assert_eq!(
+            Radix::new(2)
+                .unwrap()
+                .encode_to_slice(greek, b"\x1b", false, &mut buffer),
+            Err(ConversionError::NonAsciiSymbol { ch: 'α' })
+        );
+        assert_eq!(
+            Radix::new(2).unwrap().encode(greek, b"\x1b", false),
+            Ok("αβγδ".to_string())
+        );
+    }
+
+    #[test]
+    fn should_round_trip_every_radix() {
+        let data: Vec<u8> = (0..=255_u8).rev().collect();
+        for length in 0..=16 {
+            let slice = &data[..length];
+            let binary = BINARY.encode(&BinaryDigits {}, slice, false).unwrap();
+            assert_eq!(
+                BINARY.decode(&BinaryDigits {}, &binary, false).unwrap(),
+                slice
+            );
+            let octal = OCTAL.encode(&OctalDigits {}, slice, false).unwrap();
+            assert_eq!(OCTAL.decode(&OctalDigits {}, &octal, false).unwrap(), slice);
+            let hex = HEX.encode(&ClassicHex {}, slice, false).unwrap();
+            assert_eq!(HEX.decode(&ClassicHex {}, &hex, false).unwrap(), slice);
+            let base32 = BASE32.encode(&ClassicBase32 {}, slice, true).unwrap();
+            assert_eq!(
+                BASE32.decode(&ClassicBase32 {}, &base32, true).unwrap(),
+                slice
+            );
+            let base64 = BASE64.encode(&ClassicBase64 {}, slice, true).unwrap();
+            assert_eq!(
+                BASE64.decode(&ClassicBase64 {}, &base64, true).unwrap(),
+                slice
+            );
+        }
+    }
+}
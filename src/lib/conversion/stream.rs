@@ -0,0 +1,329 @@
+/*
+
+Incremental versions of the radix codecs, for input that's too big to hold
+in memory at once. Encoders buffer at most one partial group between writes,
+decoders at most one read's worth of text; everything else streams through.
+
+*/
+use std::io::{self, Read, Write};
+
+use super::alphabet::Alphabet;
+use super::radix::Radix;
+use super::ConversionError;
+
+/// Bytes moved to or from the inner reader/writer at a time
+const BUFFER_SIZE: usize = 4096;
+
+pub struct RadixEncoder<W: Write, T: Alphabet> {
+    writer: Option<W>,
+    alphabet: T,
+    radix: Radix,
+    padded: bool,
+    // bytes that don't make up a whole group yet
+    pending: Vec<u8>,
+    encoded: String,
+}
+
+impl<W: Write, T: Alphabet> RadixEncoder<W, T> {
+    pub fn new(writer: W, alphabet: T, radix: Radix, padded: bool) -> Self {
+        RadixEncoder {
+            writer: Some(writer),
+            alphabet,
+            radix,
+            padded,
+            pending: Vec::with_capacity(radix.bytes_per_group()),
+            encoded: String::with_capacity(BUFFER_SIZE * 2),
+        }
+    }
+
+    /// Write out the final, possibly padded, group and hand back the writer.
+    /// Dropping the encoder does the same, but swallows any error.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.write_tail()?;
+        Ok(self.writer.take().unwrap())
+    }
+
+    fn write_tail(&mut self) -> io::Result<()> {
+        let pending = std::mem::take(&mut self.pending);
+        self.write_encoded(&pending, self.padded)?;
+        self.writer.as_mut().unwrap().flush()
+    }
+
+    fn write_encoded(&mut self, data: &[u8], padded: bool) -> io::Result<()> {
+        self.encoded.clear();
+        let encoded = &mut self.encoded;
+        self.radix
+            .encode_with(&self.alphabet, data, padded, |c| encoded.push(c))
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?;
+        self.writer
+            .as_mut()
+            .unwrap()
+            .write_all(self.encoded.as_bytes())
+    }
+}
+
+impl<W: Write, T: Alphabet> Write for RadixEncoder<W, T> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let group = self.radix.bytes_per_group();
+        let mut rest = buf;
+
+        // top up the group left over from last time
+        if !self.pending.is_empty() {
+            let take = (group - self.pending.len()).min(rest.len());
+            self.pending.extend_from_slice(&rest[..take]);
+            rest = &rest[take..];
+            if self.pending.len() < group {
+                return Ok(buf.len());
+            }
+
+            let pending = std::mem::take(&mut self.pending);
+            self.write_encoded(&pending, false)?;
+        }
+
+        let whole = rest.len() / group * group;
+        for chunk in rest[..whole].chunks(BUFFER_SIZE / group * group) {
+            self.write_encoded(chunk, false)?;
+        }
+        self.pending.extend_from_slice(&rest[whole..]);
+
+        Ok(buf.len())
+    }
+
+    /// Only flushes the inner writer, a partial group can't go out before `finish`
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.as_mut().unwrap().flush()
+    }
+}
+
+impl<W: Write, T: Alphabet> Drop for RadixEncoder<W, T> {
+    fn drop(&mut self) {
+        if self.writer.is_some() {
+            let _ = self.write_tail();
+        }
+    }
+}
+
+/// Reads text and yields the bytes it encodes. ASCII whitespace is skipped,
+/// so line-wrapped input works, and errors point at a byte offset in the stream.
+pub struct RadixDecoder<R: Read, T: Alphabet> {
+    reader: R,
+    alphabet: T,
+    radix: Radix,
+    padded: bool,
+    input: Vec<u8>,
+    // symbols waiting for the rest of their group, with their stream offset
+    pending: Vec<(usize, char)>,
+    decoded: Vec<u8>,
+    position: usize,
+    consumed: usize,
+    padding_seen: bool,
+    done: bool,
+}
+
+impl<R: Read, T: Alphabet> RadixDecoder<R, T> {
+    pub fn new(reader: R, alphabet: T, radix: Radix, padded: bool) -> Self {
+        RadixDecoder {
+            reader,
+            alphabet,
+            radix,
+            padded,
+            input: vec![0; BUFFER_SIZE],
+            pending: Vec::with_capacity(BUFFER_SIZE + radix.symbols_per_group()),
+            decoded: Vec::with_capacity(BUFFER_SIZE),
+            position: 0,
+            consumed: 0,
+            padding_seen: false,
+            done: false,
+        }
+    }
+
+    fn fill(&mut self) -> io::Result<()> {
+        self.decoded.clear();
+        self.position = 0;
+
+        let read = loop {
+            match self.reader.read(&mut self.input) {
+                Ok(read) => break read,
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error),
+            }
+        };
+
+        if read == 0 {
+            self.done = true;
+            return self.decode_pending(true);
+        }
+
+        let consumed = self.consumed;
+        self.pending.extend(
+            self.input[..read]
+                .iter()
+                .enumerate()
+                .filter(|(_, byte)| !byte.is_ascii_whitespace())
+                .map(|(i, &byte)| (consumed + i, byte as char)),
+        );
+        self.consumed += read;
+
+        self.decode_pending(false)
+    }
+
+    /// Decode every whole group we have, or everything once the input is over
+    fn decode_pending(&mut self, at_end: bool) -> io::Result<()> {
+        let group = self.radix.symbols_per_group();
+        let ready = match at_end {
+            true => self.pending.len(),
+            false => self.pending.len() / group * group,
+        };
+        if ready == 0 {
+            return Ok(());
+        }
+
+        // a padded group was the last one, nothing may follow it
+        if self.padding_seen {
+            return Err(invalid_data(ConversionError::InvalidPadding));
+        }
+
+        let batch = &self.pending[..ready];
+        let decoded = &mut self.decoded;
+        self.radix
+            .decode_with(
+                &self.alphabet,
+                batch.iter().map(|&(_, c)| c),
+                ready,
+                self.padded,
+                |byte| decoded.push(byte),
+            )
+            .map_err(|error| match error {
+                ConversionError::InvalidCharacter { index, ch } => {
+                    invalid_data(ConversionError::InvalidCharacter {
+                        index: batch[index].0,
+                        ch,
+                    })
+                }
+                _ => invalid_data(error),
+            })?;
+
+        self.padding_seen = self.padded
+            && self.radix.bytes_per_group() > 1
+            && Some(batch[ready - 1].1) == self.alphabet.get_padding_char();
+        self.pending.drain(..ready);
+
+        Ok(())
+    }
+}
+
+impl<R: Read, T: Alphabet> Read for RadixDecoder<R, T> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.position == self.decoded.len() {
+            if self.done {
+                return Ok(0);
+            }
+            self.fill()?;
+        }
+
+        let available = &self.decoded[self.position..];
+        let count = available.len().min(buf.len());
+        buf[..count].copy_from_slice(&available[..count]);
+        self.position += count;
+
+        Ok(count)
+    }
+}
+
+fn invalid_data(error: ConversionError) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lib::conversion::base32::{self, ClassicBase32};
+    use crate::lib::conversion::radix::BASE32;
+
+    /// Hands out at most `step` bytes per read, to hit every group boundary
+    struct Trickle<'a> {
+        data: &'a [u8],
+        step: usize,
+    }
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let count = self.step.min(buf.len()).min(self.data.len());
+            buf[..count].copy_from_slice(&self.data[..count]);
+            self.data = &self.data[count..];
+            Ok(count)
+        }
+    }
+
+    #[test]
+    fn should_encode_in_pieces() {
+        let data: Vec<u8> = (0..=255_u8).cycle().take(10_000).collect();
+        for step in [1, 2, 3, 4, 5, 7, 4096, 5000] {
+            let mut encoder = RadixEncoder::new(Vec::new(), ClassicBase32 {}, BASE32, true);
+            data.chunks(step)
+                .for_each(|chunk| encoder.write_all(chunk).unwrap());
+            let encoded = encoder.finish().unwrap();
+            assert_eq!(String::from_utf8(encoded).unwrap(), base32::encode(&data));
+        }
+    }
+
+    #[test]
+    fn should_finish_on_drop() {
+        let mut out = Vec::new();
+        {
+            let mut encoder = RadixEncoder::new(&mut out, ClassicBase32 {}, BASE32, true);
+            encoder.write_all(b"foobar").unwrap();
+        }
+        assert_eq!(out, b"MZXW6YTBOI======");
+    }
+
+    #[test]
+    fn should_decode_in_pieces() {
+        let data: Vec<u8> = (0..=255_u8).cycle().take(10_000).collect();
+        let encoded = base32::encode(&data);
+        for step in [1, 3, 8, 13, 4096, 20_000] {
+            let reader = Trickle {
+                data: encoded.as_bytes(),
+                step,
+            };
+            let mut decoded = Vec::new();
+            RadixDecoder::new(reader, ClassicBase32 {}, BASE32, true)
+                .read_to_end(&mut decoded)
+                .unwrap();
+            assert_eq!(decoded, data);
+        }
+    }
+
+    #[test]
+    fn should_report_stream_offsets() {
+        let reader = Trickle {
+            data: b"MZXW6YTB\nMZ!W6YTB",
+            step: 3,
+        };
+        let error = RadixDecoder::new(reader, ClassicBase32 {}, BASE32, true)
+            .read_to_end(&mut Vec::new())
+            .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(
+            error
+                .into_inner()
+                .unwrap()
+                .downcast_ref::<ConversionError>(),
+            Some(&ConversionError::InvalidCharacter { index: 11, ch: '!' })
+        );
+    }
+
+    #[test]
+    fn should_refuse_anything_after_padding() {
+        for input in ["MY======MZXW6YTB", "MY======\nMY======"] {
+            let reader = Trickle {
+                data: input.as_bytes(),
+                step: 8,
+            };
+            let error = RadixDecoder::new(reader, ClassicBase32 {}, BASE32, true)
+                .read_to_end(&mut Vec::new())
+                .unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        }
+    }
+}
@@ -0,0 +1,109 @@
+/*
+
+Lookup tables for ASCII alphabets of up to 64 symbols. Encoding is an
+index into the symbol list, decoding an index into a 256-entry byte map
+where anything outside the alphabet (padding included) maps to `INVALID`.
+That keeps the per-character work branch-free, and lets a fast path check
+a whole word of lookups at once by OR-ing them together.
+
+*/
+use super::alphabet::Alphabet;
+
+/// Marks bytes outside the alphabet; its top bit is never set by a real index
+pub const INVALID: u8 = 0xFF;
+
+#[derive(Debug, Clone, Copy)]
+pub struct SymbolTable {
+    symbols: [u8; 64],
+    length: usize,
+    indices: [u8; 256],
+    padding: Option<u8>,
+}
+
+impl SymbolTable {
+    pub const fn new(symbols: &[u8], padding: Option<u8>) -> SymbolTable {
+        assert!(symbols.len() <= 64);
+
+        let mut table = SymbolTable {
+            symbols: [0; 64],
+            length: symbols.len(),
+            indices: [INVALID; 256],
+            padding,
+        };
+        let mut i = 0;
+        while i < symbols.len() {
+            assert!(symbols[i].is_ascii());
+            table.symbols[i] = symbols[i];
+            table.indices[symbols[i] as usize] = i as u8;
+            i += 1;
+        }
+        table
+    }
+
+    /// Also accept `aliases` when decoding, standing in for the symbols from `first_index` on
+    pub const fn with_aliases(mut self, aliases: &[u8], first_index: u8) -> SymbolTable {
+        let mut i = 0;
+        while i < aliases.len() {
+            self.indices[aliases[i] as usize] = first_index + i as u8;
+            i += 1;
+        }
+        self
+    }
+
+    /// Symbol for the low six bits of `index`
+    #[inline]
+    pub fn symbol(&self, index: u8) -> u8 {
+        self.symbols[(index & 63) as usize]
+    }
+
+    /// Index of `byte` in the alphabet, or `INVALID`
+    #[inline]
+    pub fn index(&self, byte: u8) -> u8 {
+        self.indices[byte as usize]
+    }
+}
+
+impl Alphabet for SymbolTable {
+    fn get_char_for_index(&self, index: u8) -> Option<char> {
+        ((index as usize) < self.length).then(|| self.symbols[index as usize] as char)
+    }
+
+    fn get_index_for_char(&self, character: char) -> Option<u8> {
+        if !character.is_ascii() {
+            return None;
+        }
+        match self.indices[character as usize] {
+            INVALID => None,
+            index => Some(index),
+        }
+    }
+
+    fn get_padding_char(&self) -> Option<char> {
+        self.padding.map(char::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIGITS: SymbolTable =
+        SymbolTable::new(b"0123456789ABCDEF", None).with_aliases(b"abcdef", 10);
+
+    #[test]
+    fn should_map_both_ways() {
+        assert_eq!(DIGITS.get_char_for_index(11), Some('B'));
+        assert_eq!(DIGITS.get_char_for_index(16), None);
+        assert_eq!(DIGITS.get_index_for_char('B'), Some(11));
+        assert_eq!(DIGITS.get_index_for_char('b'), Some(11));
+        assert_eq!(DIGITS.get_index_for_char('g'), None);
+        assert_eq!(DIGITS.index(b'g'), INVALID);
+    }
+
+    #[test]
+    fn should_not_truncate_wide_characters() {
+        // U+0141 would land on 'A' if it were cut down to a byte
+        assert_eq!(DIGITS.get_index_for_char('\u{141}'), None);
+        assert_eq!(DIGITS.get_index_for_char('\u{130}'), None);
+    }
+}
@@ -0,0 +1,187 @@
+/*
+
+uuencode, the Unix-to-Unix mail attachment format. Each line starts with a
+character giving how many bytes it holds (at most 45), followed by those
+bytes in groups of three written as four characters from ` ` to `_`, with
+`` ` `` standing in for zero. The body sits between a `begin <mode> <name>`
+line and an `end` line.
+
+*/
+use super::ConversionError;
+
+/// Bytes per full line, which comes out as 60 characters plus the length
+const LINE_BYTES: usize = 45;
+
+fn encode_symbol(value: u8) -> char {
+    match value {
+        0 => '`',
+        _ => (value + 0x20) as char,
+    }
+}
+
+fn decode_symbol(character: char) -> Option<u8> {
+    match character {
+        ' '..='`' => Some((character as u8 - 0x20) & 0x3F),
+        _ => None,
+    }
+}
+
+/// Encode `data` as a complete file called `name`, readable by everyone.
+pub fn encode(data: &[u8], name: &str) -> String {
+    let mut out = format!("begin 644 {}\n", name);
+    for line in data.chunks(LINE_BYTES) {
+        out.push(encode_symbol(line.len() as u8));
+        for group in line.chunks(3) {
+            let value = (0..3).fold(0_u32, |value, i| {
+                value << 8 | *group.get(i).unwrap_or(&0) as u32
+            });
+            (0..4)
+                .rev()
+                .for_each(|i| out.push(encode_symbol((value >> (6 * i)) as u8 & 0x3F)));
+        }
+        out.push('\n');
+    }
+    out.push_str("`\nend\n");
+    out
+}
+
+/// Decode a complete file, `begin` and `end` lines included. Every line
+/// must be exactly as long as its length character says.
+pub fn decode(text: &str) -> Result<Vec<u8>, ConversionError> {
+    decode_with(text, false)
+}
+
+/// Decode what's left after mail has been at it: the `begin`/`end` lines may
+/// be missing, trailing spaces stripped (they're zeroes) and lines may carry
+/// extra characters past their data, like the checksum some encoders add.
+pub fn decode_lenient(text: &str) -> Result<Vec<u8>, ConversionError> {
+    decode_with(text, true)
+}
+
+fn decode_with(text: &str, lenient: bool) -> Result<Vec<u8>, ConversionError> {
+    let mut out = Vec::with_capacity(text.len() / 4 * 3);
+    let mut lines = Vec::new();
+    let mut line_start = 0;
+    for line in text.split('\n') {
+        lines.push((line_start, line.trim_end_matches('\r')));
+        line_start += line.chars().count() + 1;
+    }
+    let mut lines = lines
+        .into_iter()
+        .filter(|(_, line)| !line.is_empty())
+        .peekable();
+
+    match lines.peek() {
+        Some((_, line)) if line.starts_with("begin ") => {
+            lines.next();
+        }
+        _ if lenient => (),
+        _ => return Err(ConversionError::MissingBoundary),
+    }
+
+    for (offset, line) in &mut lines {
+        if line == "end" && lenient {
+            return Ok(out);
+        }
+        let length = decode_line(line, offset, lenient, &mut out)?;
+        // an empty line closes the body
+        if length == 0 {
+            break;
+        }
+    }
+
+    match lines.next() {
+        Some((_, "end")) => Ok(out),
+        _ if lenient => Ok(out),
+        _ => Err(ConversionError::MissingBoundary),
+    }
+}
+
+/// Decode one line at char `offset` into `out`, returning how many bytes it held
+fn decode_line(
+    line: &str,
+    offset: usize,
+    lenient: bool,
+    out: &mut Vec<u8>,
+) -> Result<usize, ConversionError> {
+    let characters: Vec<char> = line.chars().collect();
+    let length = decode_symbol(characters[0]).ok_or(ConversionError::InvalidCharacter {
+        index: offset,
+        ch: characters[0],
+    })? as usize;
+
+    let symbols = length.div_ceil(3) * 4;
+    let found = characters.len() - 1;
+    if found != symbols && !lenient {
+        return Err(ConversionError::InvalidLength);
+    }
+
+    let mut value = 0_u32;
+    for index in 1..=symbols {
+        // trailing spaces that got stripped were zeroes
+        let ch = *characters.get(index).unwrap_or(&' ');
+        let symbol = decode_symbol(ch).ok_or(ConversionError::InvalidCharacter {
+            index: offset + index,
+            ch,
+        })?;
+        value = value << 6 | symbol as u32;
+        if index % 4 == 0 {
+            let group = &value.to_be_bytes()[1..];
+            let done = (index / 4 - 1) * 3;
+            out.extend_from_slice(&group[..(length - done).min(3)]);
+            value = 0;
+        }
+    }
+
+    Ok(length)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_convert_back_and_forth() {
+        let data: Vec<u8> = (0..=255_u8).cycle().take(1000).collect();
+        let encoded = encode(&data, "data.bin");
+        assert!(encoded.starts_with("begin 644 data.bin\nM"));
+        assert!(encoded.ends_with("\n`\nend\n"));
+        assert_eq!(decode(&encoded).unwrap(), data);
+        assert_eq!(decode_lenient(&encoded).unwrap(), data);
+    }
+
+    #[test]
+    fn should_match_the_classic_example() {
+        let encoded = encode(b"Cat", "cat.txt");
+        assert_eq!(encoded, "begin 644 cat.txt\n#0V%T\n`\nend\n");
+        assert_eq!(decode(&encoded).unwrap(), b"Cat");
+    }
+
+    #[test]
+    fn should_insist_on_structure() {
+        assert_eq!(
+            decode("#0V%T\n`\nend\n"),
+            Err(ConversionError::MissingBoundary)
+        );
+        assert_eq!(
+            decode("begin 644 cat.txt\n#0V%T\n`\n"),
+            Err(ConversionError::MissingBoundary)
+        );
+        assert_eq!(
+            decode("begin 644 cat.txt\n#0V%\n`\nend\n"),
+            Err(ConversionError::InvalidLength)
+        );
+        assert_eq!(
+            decode("begin 644 cat.txt\n#0V%a\n`\nend\n"),
+            Err(ConversionError::InvalidCharacter { index: 22, ch: 'a' })
+        );
+    }
+
+    #[test]
+    fn should_survive_mail_leniently() {
+        // a bare body, a checksum character, and trailing spaces stripped
+        assert_eq!(decode_lenient("#0V%TX\n").unwrap(), b"Cat");
+        assert_eq!(decode_lenient("\"``\n").unwrap(), b"\0\0");
+        assert_eq!(decode_lenient("\"\n").unwrap(), b"\0\0");
+    }
+}
@@ -1,9 +1,8 @@
-use crate::lib::{conversion::hex};
-use itertools::Itertools;
+use crate::lib::conversion::hex;
 use rayon::prelude::*;
 use std::error::Error;
 
-use super::analysis::pick_best_english_string;
+use super::analysis::{ByteLikelihood, Scorer};
 
 #[derive(Debug)]
 pub struct XoxSingleCharAnswer {
@@ -35,26 +34,31 @@ pub fn xor_bytes_with_char(bytes: &[u8], operand: u8) -> Vec<u8> {
 
 /// Given a byte slice, figure out the secret message that was XOR'd against a single u8
 pub fn guess_xor_message_one_char(bytes: &[u8]) -> Result<XoxSingleCharAnswer, Box<dyn Error>> {
-    let strings: Vec<_> = (0..=255_u8)
+    guess_xor_message_one_char_using_scorer(&ByteLikelihood::default(), bytes)
+}
+
+/// Same as `guess_xor_message_one_char`, but `scorer` decides which key's output reads best
+pub fn guess_xor_message_one_char_using_scorer<S: Scorer + ?Sized>(
+    scorer: &S,
+    bytes: &[u8],
+) -> Result<XoxSingleCharAnswer, Box<dyn Error>> {
+    let scores: Vec<_> = (0..=255_u8)
         .into_par_iter()
-        .map(|n| (n, xor_bytes_with_char(bytes, n)))
-        .map(|(byte, bytes)| (byte as char, String::from_utf8_lossy(&bytes).to_string()))
+        .map(|n| (n, scorer.score(&xor_bytes_with_char(bytes, n))))
         .collect();
-    let decoded_strings = strings
-        .iter()
-        .map(|(_, string)| string.clone())
-        .collect_vec();
 
-    let best_sentence = pick_best_english_string(&decoded_strings);
-    let best_entry = strings
+    // ties go to the smallest key
+    let (best_key, _) = scores
         .iter()
-        .find(|(_, ans)| ans == best_sentence)
-        .unwrap();
+        .fold(scores[0], |best, &entry| match entry.1 > best.1 {
+            true => entry,
+            false => best,
+        });
 
     let answer = XoxSingleCharAnswer {
-        answer: best_entry.0,
+        answer: best_key as char,
         original: String::from_utf8_lossy(bytes).to_string(),
-        decoded: best_entry.1.clone(),
+        decoded: String::from_utf8_lossy(&xor_bytes_with_char(bytes, best_key)).to_string(),
     };
 
     Ok(answer)
@@ -71,6 +75,7 @@ pub fn xor_bytes_with_repeating_pattern(bytes: &[u8], pattern: &[u8]) -> Vec<u8>
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::lib::analysis::ChiSquared;
     type TestResult = Result<(), Box<dyn std::error::Error>>;
 
     #[test]
@@ -84,4 +89,19 @@ mod tests {
         assert_eq!(answer.answer, 't');
         Ok(())
     }
+
+    #[test]
+    fn should_get_xor_single_char_using_any_scorer() -> TestResult {
+        let original = "Cooking MC's like a pound of bacon";
+        let xored = xor_bytes_with_char(original.as_bytes(), b'X');
+
+        let answer = guess_xor_message_one_char_using_scorer(&ChiSquared::default(), &xored)?;
+        assert_eq!(answer.decoded, original);
+
+        // a scorer that only cares about the first byte being a capital C
+        let starts_with_c = |candidate: &[u8]| (candidate.first() == Some(&b'C')) as u8 as f64;
+        let answer = guess_xor_message_one_char_using_scorer(&starts_with_c, &xored)?;
+        assert_eq!(answer.answer, 'X');
+        Ok(())
+    }
 }
@@ -1,3 +1,4 @@
 pub mod analysis;
 pub mod conversion;
 pub mod manipulate;
+pub mod recipe;
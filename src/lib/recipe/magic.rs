@@ -0,0 +1,197 @@
+/*
+
+"Magic": peel layered encodings without being told what they are. Starting
+from the input, every decode step that succeeds is tried, level by level,
+down to a fixed depth. Each level only keeps its best-looking outputs, so
+the search stays bounded however many steps happen to apply.
+
+*/
+use std::collections::HashSet;
+
+use super::{
+    Base32Decode, Base64Decode, HexDecode, Recipe, Reverse, Transform, UrlDecode, XorByte,
+};
+use crate::lib::analysis::english_byte_score;
+use crate::lib::manipulate::guess_xor_message_one_char;
+
+#[derive(Debug, Clone, Copy)]
+pub struct MagicOptions {
+    /// Most steps in any chain
+    pub depth: usize,
+    /// Outputs carried from one level to the next
+    pub beam_width: usize,
+    /// Candidates handed back
+    pub results: usize,
+}
+
+impl Default for MagicOptions {
+    fn default() -> Self {
+        MagicOptions {
+            depth: 4,
+            beam_width: 32,
+            results: 5,
+        }
+    }
+}
+
+/// An output the search reached, and how it got there
+#[derive(Debug)]
+pub struct Candidate {
+    pub recipe: Recipe,
+    pub output: Vec<u8>,
+    /// The LARGER the number, the more it resembles english
+    pub score: f64,
+}
+
+/// Decode steps worth trying on anything. Single-byte XOR is handled on its own.
+const DECODERS: [&dyn Transform; 6] = [
+    &HexDecode,
+    &Base64Decode { url_safe: false },
+    &Base64Decode { url_safe: true },
+    &Base32Decode,
+    &UrlDecode,
+    &Reverse,
+];
+
+/// Taken off the score for every step, so that output two recipes agree on
+/// (a palindrome, say) is credited to the shorter one
+const STEP_COST: f64 = 1e-9;
+
+struct Node {
+    steps: Vec<String>,
+    output: Vec<u8>,
+    score: f64,
+}
+
+/// The best plaintexts `input` unwraps to, best first.
+pub fn magic(input: &[u8]) -> Vec<Candidate> {
+    magic_with_options(input, &MagicOptions::default())
+}
+
+pub fn magic_with_options(input: &[u8], options: &MagicOptions) -> Vec<Candidate> {
+    let mut seen: HashSet<Vec<u8>> = HashSet::from([input.to_vec()]);
+    let mut found = vec![Node {
+        steps: Vec::new(),
+        output: input.to_vec(),
+        score: english_byte_score(input),
+    }];
+    let mut frontier = vec![0];
+
+    for _ in 0..options.depth {
+        let mut next: Vec<Node> = frontier
+            .iter()
+            .flat_map(|&i| expand(&found[i]))
+            .filter(|node| seen.insert(node.output.clone()))
+            .collect();
+        if next.is_empty() {
+            break;
+        }
+
+        next.sort_by(|a, b| b.score.total_cmp(&a.score));
+        next.truncate(options.beam_width);
+        frontier = (found.len()..found.len() + next.len()).collect();
+        found.extend(next);
+    }
+
+    found.sort_by(|a, b| b.score.total_cmp(&a.score));
+    found
+        .into_iter()
+        .take(options.results)
+        .map(|node| Candidate {
+            // every step describes itself in recipe syntax, so this always parses
+            recipe: Recipe::parse(&node.steps.join(" | ")).unwrap(),
+            output: node.output,
+            score: node.score,
+        })
+        .collect()
+}
+
+/// Every node one step below `node`
+fn expand(node: &Node) -> Vec<Node> {
+    let last = node.steps.last().map(String::as_str);
+
+    // XORing twice gets us nowhere, the two keys fold into one
+    let xor = match last.is_some_and(|step| step.starts_with("xor")) {
+        true => None,
+        false => guess_xor_message_one_char(&node.output)
+            .ok()
+            .map(|guess| XorByte {
+                byte: guess.answer as u8,
+            }),
+    };
+
+    DECODERS
+        .into_iter()
+        // and neither does reversing twice
+        .filter(|decoder| !(last == Some("reverse") && decoder.describe() == "reverse"))
+        .chain(xor.as_ref().map(|xor| xor as &dyn Transform))
+        .filter_map(|step| {
+            let output = step.apply(&node.output).ok()?;
+            if output.is_empty() {
+                return None;
+            }
+            let mut steps = node.steps.clone();
+            steps.push(step.describe());
+            Some(Node {
+                score: english_byte_score(&output) - STEP_COST * steps.len() as f64,
+                steps,
+                output,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lib::conversion::{base64, hex};
+
+    const CHALLENGE_3: &str =
+        "1b37373331363f78151b7f2b783431333d78397828372d363c78373e783a393b3736";
+
+    #[test]
+    fn should_peel_base64_of_hex_of_xor() {
+        let blob = base64::encode(CHALLENGE_3.as_bytes());
+        let best = &magic(blob.as_bytes())[0];
+        assert_eq!(best.output, b"Cooking MC's like a pound of bacon");
+        assert_eq!(best.recipe.to_string(), "base64d | hexd | xor(byte=0x58)");
+        assert_eq!(best.recipe.apply(blob.as_bytes()).unwrap(), best.output);
+    }
+
+    #[test]
+    fn should_undo_reversal() {
+        let text = "Now that the party is jumping, with the bass kicked in";
+        let blob: String = hex::encode(text.as_bytes()).chars().rev().collect();
+        let best = &magic(blob.as_bytes())[0];
+        assert_eq!(best.output, text.as_bytes());
+        assert_eq!(best.recipe.to_string(), "reverse | hexd");
+    }
+
+    #[test]
+    fn should_not_mistake_accents_for_noise() {
+        // counting printable ASCII put an XOR of the base64 itself ahead of this
+        let text = "Déjà vu: the résumé of a naïve café owner";
+        let blob = base64::encode(text.as_bytes());
+        let best = &magic(blob.as_bytes())[0];
+        assert_eq!(best.output, text.as_bytes());
+        assert_eq!(best.recipe.to_string(), "base64d");
+    }
+
+    #[test]
+    fn should_stay_within_bounds() {
+        let blob = base64::encode(CHALLENGE_3.as_bytes());
+        let shallow = MagicOptions {
+            depth: 2,
+            beam_width: 4,
+            results: 3,
+        };
+        let candidates = magic_with_options(blob.as_bytes(), &shallow);
+        assert_eq!(candidates.len(), 3);
+        assert!(candidates.iter().all(|c| c.recipe.steps().len() <= 2));
+        assert!(candidates.windows(2).all(|w| w[0].score >= w[1].score));
+
+        // plain text is its own best reading
+        let text = b"Cooking MC's like a pound of bacon";
+        assert_eq!(magic(text)[0].recipe.steps().len(), 0);
+    }
+}
@@ -0,0 +1,346 @@
+/*
+
+Recipes: chains of small byte transforms, CyberChef style. Each step takes
+the previous step's output, so `base64d | xor(key=ICE) | hex` undoes the
+base64, XORs the result against a repeating key and hex encodes it.
+
+Operations and their arguments:
+
+    hex       hex(case=lower|upper)      hexd
+    base64    base64(alphabet=url)       base64d(alphabet=standard|url)
+    base32                               base32d
+    url       percent-encoding           urld
+    qp        quoted-printable           qpd
+    xor       xor(key=text) or xor(hexkey=...) or xor(byte=0x58)
+    reverse
+
+*/
+mod magic;
+mod parse;
+mod transform;
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::lib::conversion::hex;
+
+use parse::{parse_steps, Step};
+
+pub use magic::{magic, magic_with_options, Candidate, MagicOptions};
+pub use parse::RecipeError;
+pub use transform::{
+    Base32Decode, Base32Encode, Base64Decode, Base64Encode, HexDecode, HexEncode,
+    QuotedPrintableDecode, QuotedPrintableEncode, RepeatingXor, Reverse, Transform, UrlDecode,
+    UrlEncode, XorByte,
+};
+
+#[derive(Debug, Default)]
+pub struct Recipe {
+    steps: Vec<Box<dyn Transform>>,
+}
+
+impl Recipe {
+    /// A recipe with no steps, it hands its input straight back
+    pub fn new() -> Recipe {
+        Recipe::default()
+    }
+
+    /// Add `step` to the end of the chain
+    pub fn then<T: Transform + 'static>(mut self, step: T) -> Recipe {
+        self.steps.push(Box::new(step));
+        self
+    }
+
+    pub fn parse(text: &str) -> Result<Recipe, RecipeError> {
+        let steps = parse_steps(text)?
+            .iter()
+            .map(build)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Recipe { steps })
+    }
+
+    pub fn steps(&self) -> &[Box<dyn Transform>] {
+        &self.steps
+    }
+
+    /// Run every step in turn. A failure names the step it happened in.
+    pub fn apply(&self, input: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+        let mut data = input.to_vec();
+        for (i, step) in self.steps.iter().enumerate() {
+            data = step.apply(&data).map_err(|source| StepError {
+                step: i + 1,
+                operation: step.describe(),
+                source,
+            })?;
+        }
+        Ok(data)
+    }
+}
+
+impl Transform for Recipe {
+    fn apply(&self, input: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+        Recipe::apply(self, input)
+    }
+
+    fn describe(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl FromStr for Recipe {
+    type Err = RecipeError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Recipe::parse(text)
+    }
+}
+
+/// Writes the recipe back out in a form `parse` reads
+impl fmt::Display for Recipe {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let steps: Vec<String> = self.steps.iter().map(|step| step.describe()).collect();
+        write!(f, "{}", steps.join(" | "))
+    }
+}
+
+/// A step that failed while a recipe ran. Steps count from 1.
+#[derive(Debug)]
+pub struct StepError {
+    pub step: usize,
+    pub operation: String,
+    pub source: Box<dyn Error>,
+}
+
+impl fmt::Display for StepError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "step {} ({}) failed: {}",
+            self.step, self.operation, self.source
+        )
+    }
+}
+
+impl Error for StepError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
+
+fn build(step: &Step) -> Result<Box<dyn Transform>, RecipeError> {
+    let allowed: &[&str] = match step.name.as_str() {
+        "hex" => &["case"],
+        "base64" | "base64d" => &["alphabet"],
+        "xor" => &["key", "hexkey", "byte"],
+        _ => &[],
+    };
+    if let Some((name, _)) = step
+        .arguments
+        .iter()
+        .find(|(name, _)| !allowed.contains(&name.as_str()))
+    {
+        return Err(RecipeError::UnknownArgument {
+            operation: step.name.clone(),
+            name: name.clone(),
+        });
+    }
+
+    let transform: Box<dyn Transform> = match step.name.as_str() {
+        "hex" => Box::new(HexEncode {
+            lowercase: choose(step, "case", &["upper", "lower"])? == 1,
+        }),
+        "hexd" => Box::new(HexDecode),
+        "base64" => Box::new(Base64Encode {
+            url_safe: choose(step, "alphabet", &["standard", "url"])? == 1,
+        }),
+        "base64d" => Box::new(Base64Decode {
+            url_safe: choose(step, "alphabet", &["standard", "url"])? == 1,
+        }),
+        "base32" => Box::new(Base32Encode),
+        "base32d" => Box::new(Base32Decode),
+        "url" => Box::new(UrlEncode),
+        "urld" => Box::new(UrlDecode),
+        "qp" => Box::new(QuotedPrintableEncode),
+        "qpd" => Box::new(QuotedPrintableDecode),
+        "reverse" => Box::new(Reverse),
+        "xor" => xor(step)?,
+        _ => {
+            return Err(RecipeError::UnknownOperation {
+                position: step.position,
+                name: step.name.clone(),
+            })
+        }
+    };
+    Ok(transform)
+}
+
+/// Index of the argument's value among `choices`, the first being the default
+fn choose(step: &Step, name: &str, choices: &[&str]) -> Result<usize, RecipeError> {
+    let Some(value) = step.argument(name) else {
+        return Ok(0);
+    };
+    choices
+        .iter()
+        .position(|choice| choice.eq_ignore_ascii_case(value))
+        .ok_or_else(|| invalid_argument(step, name, value))
+}
+
+fn xor(step: &Step) -> Result<Box<dyn Transform>, RecipeError> {
+    match step.arguments.as_slice() {
+        [(name, value)] if name == "key" && !value.is_empty() => Ok(Box::new(RepeatingXor {
+            key: value.as_bytes().to_vec(),
+        })),
+        [(name, value)] if name == "hexkey" => match hex::decode_lenient(value) {
+            Ok(key) if !key.is_empty() => Ok(Box::new(RepeatingXor { key })),
+            _ => Err(invalid_argument(step, name, value)),
+        },
+        [(name, value)] if name == "byte" => {
+            let byte = match value.strip_prefix("0x").or(value.strip_prefix("0X")) {
+                Some(digits) => u8::from_str_radix(digits, 16),
+                None => value.parse(),
+            };
+            byte.map(|byte| Box::new(XorByte { byte }) as Box<dyn Transform>)
+                .map_err(|_| invalid_argument(step, name, value))
+        }
+        [(name, value)] => Err(invalid_argument(step, name, value)),
+        [(other, _), (name, _), ..] => Err(RecipeError::ConflictingArgument {
+            operation: step.name.clone(),
+            name: name.clone(),
+            other: other.clone(),
+        }),
+        [] => Err(RecipeError::MissingArgument {
+            operation: step.name.clone(),
+            name: "key".to_string(),
+        }),
+    }
+}
+
+fn invalid_argument(step: &Step, name: &str, value: &str) -> RecipeError {
+    RecipeError::InvalidArgument {
+        operation: step.name.clone(),
+        name: name.to_string(),
+        value: value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lib::manipulate;
+
+    const CHALLENGE_5: &str =
+        "Burning 'em, if you ain't quick and nimble\nI go crazy when I hear a cymbal";
+
+    #[test]
+    fn should_solve_challenge_5() {
+        let recipe: Recipe = "xor(key=ICE) | hex(case=lower)".parse().unwrap();
+        let output = recipe.apply(CHALLENGE_5.as_bytes()).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "0b3637272a2b2e63622c2e69692a23693a2a3c6324202d623d63343c2a26226324272765272a282b2f20430a652e2c652a3124333a653e2b2027630c692b20283165286326302e27282f"
+        );
+    }
+
+    #[test]
+    fn should_solve_challenge_6_with_the_key() {
+        let ciphertext = include_str!("../../../files/pset1challenge6.txt");
+        let recipe =
+            Recipe::parse(r#"base64d | xor(key="Terminator X: Bring the noise")"#).unwrap();
+        let plaintext = String::from_utf8(recipe.apply(ciphertext.as_bytes()).unwrap()).unwrap();
+        assert!(plaintext.starts_with("I'm back and I'm ringin' the bell"));
+    }
+
+    #[test]
+    fn should_build_the_same_as_it_parses() {
+        let built = Recipe::new()
+            .then(HexDecode)
+            .then(XorByte { byte: 0x58 })
+            .then(Base64Encode { url_safe: true });
+        let parsed = Recipe::parse("hexd | xor(byte=88) | base64(alphabet=URL)").unwrap();
+        let input = b"1b37373331363f78151b7f2b783431333d78397828372d363c78373e783a393b3736";
+        assert_eq!(built.apply(input).unwrap(), parsed.apply(input).unwrap());
+        assert_eq!(built.to_string(), parsed.to_string());
+        assert_eq!(
+            manipulate::xor_bytes_with_char(&hex::decode_lenient("1b37").unwrap(), 0x58),
+            b"Co"
+        );
+    }
+
+    #[test]
+    fn should_round_trip_through_display() {
+        let text = r#"base64d(alphabet=url) | xor(key="Terminator X: Bring the noise") | xor(hexkey=00ff) | xor(byte=0x20) | hex(case=lower) | hexd | base32 | base32d | url | urld | qp | qpd | reverse"#;
+        let recipe = Recipe::parse(text).unwrap();
+        assert_eq!(recipe.to_string(), text);
+        assert_eq!(
+            Recipe::parse(&recipe.to_string()).unwrap().to_string(),
+            text
+        );
+        assert_eq!(recipe.steps().len(), 13);
+
+        let data: Vec<u8> = (0..=255_u8).collect();
+        let there_and_back = Recipe::parse(
+            "base64 | reverse | url | qp | qpd | urld | reverse | base64d | hex | hexd",
+        )
+        .unwrap()
+        .apply(&data)
+        .unwrap();
+        assert_eq!(there_and_back, data);
+        assert_eq!(Recipe::new().apply(&data).unwrap(), data);
+    }
+
+    #[test]
+    fn should_refuse_what_it_cannot_build() {
+        let error = |text| Recipe::parse(text).unwrap_err();
+        assert_eq!(
+            error("hex | rot13"),
+            RecipeError::UnknownOperation {
+                position: 6,
+                name: "rot13".to_string()
+            }
+        );
+        assert_eq!(
+            error("hexd(case=lower)"),
+            RecipeError::UnknownArgument {
+                operation: "hexd".to_string(),
+                name: "case".to_string()
+            }
+        );
+        assert!(matches!(
+            error("hex(case=title)"),
+            RecipeError::InvalidArgument { .. }
+        ));
+        assert!(matches!(
+            error("xor(byte=256)"),
+            RecipeError::InvalidArgument { .. }
+        ));
+        assert!(matches!(
+            error("xor(hexkey=xyz)"),
+            RecipeError::InvalidArgument { .. }
+        ));
+        assert_eq!(
+            error("xor(key=a, byte=1)"),
+            RecipeError::ConflictingArgument {
+                operation: "xor".to_string(),
+                name: "byte".to_string(),
+                other: "key".to_string()
+            }
+        );
+        assert!(matches!(error("xor"), RecipeError::MissingArgument { .. }));
+    }
+
+    #[test]
+    fn should_say_which_step_failed() {
+        let error = Recipe::parse("hexd | base64d")
+            .unwrap()
+            .apply(b"2a2a")
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "step 2 (base64d) failed: invalid character '*' at index 0"
+        );
+        let error = error.downcast::<StepError>().unwrap();
+        assert_eq!(error.step, 2);
+        assert!(error.source().is_some());
+    }
+}
@@ -0,0 +1,303 @@
+/*
+
+The recipe language. Steps are separated by `|`, and each is a name with
+optional `name=value` arguments in parentheses:
+
+    base64d | xor(key="Terminator X: Bring the noise") | hex(case=lower)
+
+Values can be quoted to hold spaces or punctuation; inside quotes a
+backslash escapes the next character. Positions count chars from the start.
+
+*/
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecipeError {
+    /// Not something we know how to do
+    UnknownOperation { position: usize, name: String },
+    /// The operation takes no argument by that name
+    UnknownArgument { operation: String, name: String },
+    /// The argument is known but its value makes no sense
+    InvalidArgument {
+        operation: String,
+        name: String,
+        value: String,
+    },
+    /// The operation can't run without this argument
+    MissingArgument { operation: String, name: String },
+    /// `name` was given on top of `other`, and the operation takes only one of them
+    ConflictingArgument {
+        operation: String,
+        name: String,
+        other: String,
+    },
+    /// The text doesn't follow the grammar at all
+    Syntax {
+        position: usize,
+        expected: &'static str,
+    },
+}
+
+impl fmt::Display for RecipeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecipeError::UnknownOperation { position, name } => {
+                write!(f, "unknown operation {:?} at index {}", name, position)
+            }
+            RecipeError::UnknownArgument { operation, name } => {
+                write!(f, "{} takes no argument {:?}", operation, name)
+            }
+            RecipeError::InvalidArgument {
+                operation,
+                name,
+                value,
+            } => write!(f, "invalid value {:?} for {}({})", value, operation, name),
+            RecipeError::MissingArgument { operation, name } => {
+                write!(f, "{} needs an argument {:?}", operation, name)
+            }
+            RecipeError::ConflictingArgument {
+                operation,
+                name,
+                other,
+            } => write!(
+                f,
+                "{} can't take {:?} as well as {:?}",
+                operation, name, other
+            ),
+            RecipeError::Syntax { position, expected } => {
+                write!(f, "expected {} at index {}", expected, position)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RecipeError {}
+
+/// One step as written, before we know whether it means anything
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Step {
+    pub position: usize,
+    pub name: String,
+    pub arguments: Vec<(String, String)>,
+}
+
+impl Step {
+    /// Value of the argument `name`, if it was given
+    pub fn argument(&self, name: &str) -> Option<&str> {
+        self.arguments
+            .iter()
+            .find(|(argument, _)| argument == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+pub(super) fn parse_steps(text: &str) -> Result<Vec<Step>, RecipeError> {
+    let mut parser = Parser {
+        characters: text.chars().collect(),
+        position: 0,
+    };
+
+    let mut steps = Vec::new();
+    parser.skip_whitespace();
+    if parser.at_end() {
+        return Ok(steps);
+    }
+
+    loop {
+        steps.push(parser.step()?);
+        parser.skip_whitespace();
+        if parser.at_end() {
+            return Ok(steps);
+        }
+        parser.expect('|', "'|' between steps")?;
+        parser.skip_whitespace();
+    }
+}
+
+/// `value` the way a recipe would spell it, quoted only when it has to be
+pub(super) fn quote(value: &str) -> String {
+    let bare = !value.is_empty() && value.chars().all(is_bare);
+    if bare {
+        return value.to_string();
+    }
+
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        if c == '"' || c == '\\' {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn is_name(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+fn is_bare(c: char) -> bool {
+    !c.is_whitespace() && !matches!(c, '|' | '(' | ')' | ',' | '=' | '"' | '\\')
+}
+
+struct Parser {
+    characters: Vec<char>,
+    position: usize,
+}
+
+impl Parser {
+    fn at_end(&self) -> bool {
+        self.position == self.characters.len()
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.characters.get(self.position).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.position += 1;
+        }
+    }
+
+    fn expect(&mut self, c: char, expected: &'static str) -> Result<(), RecipeError> {
+        if self.peek() != Some(c) {
+            return Err(self.syntax(expected));
+        }
+        self.position += 1;
+        Ok(())
+    }
+
+    fn syntax(&self, expected: &'static str) -> RecipeError {
+        RecipeError::Syntax {
+            position: self.position,
+            expected,
+        }
+    }
+
+    fn take_while(&mut self, predicate: fn(char) -> bool) -> String {
+        let start = self.position;
+        while self.peek().is_some_and(predicate) {
+            self.position += 1;
+        }
+        self.characters[start..self.position].iter().collect()
+    }
+
+    fn name(&mut self, expected: &'static str) -> Result<String, RecipeError> {
+        let name = self.take_while(is_name);
+        match name.is_empty() {
+            true => Err(self.syntax(expected)),
+            false => Ok(name),
+        }
+    }
+
+    fn step(&mut self) -> Result<Step, RecipeError> {
+        let position = self.position;
+        let name = self.name("an operation name")?;
+        let mut arguments = Vec::new();
+
+        self.skip_whitespace();
+        if self.peek() == Some('(') {
+            self.position += 1;
+            self.skip_whitespace();
+            while self.peek() != Some(')') {
+                if !arguments.is_empty() {
+                    self.expect(',', "',' or ')'")?;
+                    self.skip_whitespace();
+                }
+                arguments.push(self.argument()?);
+                self.skip_whitespace();
+            }
+            self.position += 1;
+        }
+
+        Ok(Step {
+            position,
+            name,
+            arguments,
+        })
+    }
+
+    fn argument(&mut self) -> Result<(String, String), RecipeError> {
+        let name = self.name("an argument name")?;
+        self.skip_whitespace();
+        self.expect('=', "'=' after the argument name")?;
+        self.skip_whitespace();
+
+        let value = match self.peek() {
+            Some('"') => self.quoted()?,
+            _ => {
+                let value = self.take_while(is_bare);
+                if value.is_empty() {
+                    return Err(self.syntax("an argument value"));
+                }
+                value
+            }
+        };
+
+        Ok((name, value))
+    }
+
+    fn quoted(&mut self) -> Result<String, RecipeError> {
+        self.position += 1;
+        let mut value = String::new();
+        loop {
+            match self.peek() {
+                None => return Err(self.syntax("a closing '\"'")),
+                Some('"') => break,
+                Some('\\') => {
+                    self.position += 1;
+                    match self.peek() {
+                        Some(c) => value.push(c),
+                        None => return Err(self.syntax("a character after '\\'")),
+                    }
+                }
+                Some(c) => value.push(c),
+            }
+            self.position += 1;
+        }
+        self.position += 1;
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_steps_and_arguments() {
+        let steps = parse_steps(r#" base64d|xor( key = "a \"b\" \\c" , n=1 ) | hex "#).unwrap();
+        let names: Vec<&str> = steps.iter().map(|step| step.name.as_str()).collect();
+        assert_eq!(names, vec!["base64d", "xor", "hex"]);
+        assert_eq!(steps[1].position, 9);
+        assert_eq!(steps[1].argument("key"), Some(r#"a "b" \c"#));
+        assert_eq!(steps[1].argument("n"), Some("1"));
+        assert_eq!(steps[2].arguments, vec![]);
+        assert_eq!(parse_steps("  ").unwrap(), vec![]);
+    }
+
+    #[test]
+    fn should_quote_only_when_needed() {
+        assert_eq!(quote("ICE"), "ICE");
+        assert_eq!(quote("Terminator X"), "\"Terminator X\"");
+        assert_eq!(quote(r#"a"b\"#), r#""a\"b\\""#);
+        assert_eq!(quote(""), "\"\"");
+    }
+
+    #[test]
+    fn should_report_where_it_broke() {
+        let syntax = |text| match parse_steps(text).unwrap_err() {
+            RecipeError::Syntax { position, .. } => position,
+            error => panic!("{:?}", error),
+        };
+        assert_eq!(syntax("hex hexd"), 4);
+        assert_eq!(syntax("hex |"), 5);
+        assert_eq!(syntax("xor(key)"), 7);
+        assert_eq!(syntax("xor(key=)"), 8);
+        assert_eq!(syntax("xor(key=\"ICE)"), 13);
+        assert_eq!(syntax("xor(a=1 b=2)"), 8);
+        assert_eq!(syntax("xor(a=1"), 7);
+    }
+}
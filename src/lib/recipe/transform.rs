@@ -0,0 +1,267 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::lib::conversion::{base32, base64, hex, percent, quoted_printable};
+use crate::lib::manipulate;
+
+/// One step of a recipe: bytes in, bytes out, and it may fail.
+pub trait Transform: fmt::Debug {
+    fn apply(&self, input: &[u8]) -> Result<Vec<u8>, Box<dyn Error>>;
+
+    /// The recipe step that recreates this transform, e.g. `xor(key=ICE)`
+    fn describe(&self) -> String;
+}
+
+/// Text decoders want a `&str`, not every byte string is one
+fn as_text(input: &[u8]) -> Result<&str, Box<dyn Error>> {
+    Ok(std::str::from_utf8(input)?)
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HexEncode {
+    pub lowercase: bool,
+}
+
+impl Transform for HexEncode {
+    fn apply(&self, input: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+        let encoded = match self.lowercase {
+            true => hex::encode_lowercase(input),
+            false => hex::encode(input),
+        };
+        Ok(encoded.into_bytes())
+    }
+
+    fn describe(&self) -> String {
+        match self.lowercase {
+            true => "hex(case=lower)".to_string(),
+            false => "hex".to_string(),
+        }
+    }
+}
+
+/// Takes the lenient route: `0x` prefixes, separators and whitespace are fine
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HexDecode;
+
+impl Transform for HexDecode {
+    fn apply(&self, input: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+        Ok(hex::decode_lenient(as_text(input)?)?)
+    }
+
+    fn describe(&self) -> String {
+        "hexd".to_string()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Base64Encode {
+    pub url_safe: bool,
+}
+
+impl Transform for Base64Encode {
+    fn apply(&self, input: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+        let encoded = match self.url_safe {
+            true => base64::encode_url_safe(input),
+            false => base64::encode(input),
+        };
+        Ok(encoded.into_bytes())
+    }
+
+    fn describe(&self) -> String {
+        match self.url_safe {
+            true => "base64(alphabet=url)".to_string(),
+            false => "base64".to_string(),
+        }
+    }
+}
+
+/// Line breaks are skipped, and so is missing padding
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Base64Decode {
+    pub url_safe: bool,
+}
+
+impl Transform for Base64Decode {
+    fn apply(&self, input: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+        let text: String = as_text(input)?
+            .chars()
+            .filter(|c| !c.is_ascii_whitespace())
+            .collect();
+        let padded = text.len().is_multiple_of(4);
+        let decoded = match (self.url_safe, padded) {
+            (false, true) => base64::decode(&text),
+            (false, false) => base64::decode_unpadded(&text),
+            (true, true) => base64::decode_url_safe(&text),
+            (true, false) => base64::decode_url_safe_unpadded(&text),
+        };
+        Ok(decoded?)
+    }
+
+    fn describe(&self) -> String {
+        match self.url_safe {
+            true => "base64d(alphabet=url)".to_string(),
+            false => "base64d".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Base32Encode;
+
+impl Transform for Base32Encode {
+    fn apply(&self, input: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+        Ok(base32::encode(input).into_bytes())
+    }
+
+    fn describe(&self) -> String {
+        "base32".to_string()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Base32Decode;
+
+impl Transform for Base32Decode {
+    fn apply(&self, input: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+        let text: String = as_text(input)?
+            .chars()
+            .filter(|c| !c.is_ascii_whitespace())
+            .collect();
+        Ok(base32::decode(&text)?)
+    }
+
+    fn describe(&self) -> String {
+        "base32d".to_string()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UrlEncode;
+
+impl Transform for UrlEncode {
+    fn apply(&self, input: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+        Ok(percent::encode(input).into_bytes())
+    }
+
+    fn describe(&self) -> String {
+        "url".to_string()
+    }
+}
+
+/// Form-style, so `+` comes out as a space
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UrlDecode;
+
+impl Transform for UrlDecode {
+    fn apply(&self, input: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+        Ok(percent::decode_lenient(as_text(input)?))
+    }
+
+    fn describe(&self) -> String {
+        "urld".to_string()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct QuotedPrintableEncode;
+
+impl Transform for QuotedPrintableEncode {
+    fn apply(&self, input: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+        Ok(quoted_printable::encode(input).into_bytes())
+    }
+
+    fn describe(&self) -> String {
+        "qp".to_string()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct QuotedPrintableDecode;
+
+impl Transform for QuotedPrintableDecode {
+    fn apply(&self, input: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+        Ok(quoted_printable::decode_lenient(as_text(input)?)?)
+    }
+
+    fn describe(&self) -> String {
+        "qpd".to_string()
+    }
+}
+
+/// Bytes back to front
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Reverse;
+
+impl Transform for Reverse {
+    fn apply(&self, input: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+        Ok(input.iter().rev().copied().collect())
+    }
+
+    fn describe(&self) -> String {
+        "reverse".to_string()
+    }
+}
+
+/// XOR every byte with the same one
+#[derive(Debug, Clone, Copy)]
+pub struct XorByte {
+    pub byte: u8,
+}
+
+impl Transform for XorByte {
+    fn apply(&self, input: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+        Ok(manipulate::xor_bytes_with_char(input, self.byte))
+    }
+
+    fn describe(&self) -> String {
+        format!("xor(byte=0x{:02x})", self.byte)
+    }
+}
+
+/// XOR against `key`, repeated for as long as the input goes
+#[derive(Debug, Clone)]
+pub struct RepeatingXor {
+    pub key: Vec<u8>,
+}
+
+impl Transform for RepeatingXor {
+    fn apply(&self, input: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+        if self.key.is_empty() {
+            return Err("XOR key is empty".into());
+        }
+        Ok(manipulate::xor_bytes_with_repeating_pattern(
+            input, &self.key,
+        ))
+    }
+
+    fn describe(&self) -> String {
+        match std::str::from_utf8(&self.key) {
+            Ok(key) if key.chars().all(|c| c.is_ascii_graphic() || c == ' ') => {
+                format!("xor(key={})", super::parse::quote(key))
+            }
+            _ => format!("xor(hexkey={})", hex::encode_lowercase(&self.key)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_refuse_text_that_is_not_utf8() {
+        assert!(HexDecode.apply(&[0xFF, 0xFE]).is_err());
+        assert!(Base64Decode::default().apply(b"YWJj ZA").is_ok());
+    }
+
+    #[test]
+    fn should_xor_like_manipulate_does() {
+        let key = RepeatingXor {
+            key: b"ICE".to_vec(),
+        };
+        let single = XorByte { byte: b'X' };
+        assert_eq!(key.apply(b"abcd").unwrap(), vec![0x28, 0x21, 0x26, 0x2D]);
+        assert_eq!(single.apply(b"\x18").unwrap(), b"@");
+        assert!(RepeatingXor { key: vec![] }.apply(b"abc").is_err());
+    }
+}
@@ -92,8 +92,7 @@ I go crazy when I hear a cymbal"#;
         }
 
         let input = include_str!("../files/pset1challenge6.txt");
-        let cleaned_input: String = input.chars().filter(|c| !c.is_ascii_whitespace()).collect();
-        let input_bytes = base64::decode(&cleaned_input)?;
+        let input_bytes = base64::decode_lenient(input)?;
 
         let mut hammed: Vec<_> = (2..=40)
             .into_iter()